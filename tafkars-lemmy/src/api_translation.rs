@@ -1,77 +1,471 @@
-use lemmy_api_common::lemmy_db_schema::{CommentSortType, SortType};
-use lemmy_api_common::lemmy_db_views::structs::{CommentView, PostView};
+use lemmy_api_common::lemmy_db_schema::newtypes::{CommunityId, PersonId};
+use lemmy_api_common::lemmy_db_schema::source::community::CommunitySafe;
+use lemmy_api_common::lemmy_db_schema::source::person::PersonSafe;
+use lemmy_api_common::lemmy_db_schema::source::post::Post;
+use lemmy_api_common::lemmy_db_schema::{
+    CommentSortType, ModlogActionType, SortType, SubscribedType,
+};
+use lemmy_api_common::lemmy_db_views::structs::{
+    CommentReportView, CommentView, PostReportView, PostView,
+};
 use lemmy_api_common::lemmy_db_views_actor::structs::CommunityView;
-use lemmy_api_common::person::GetPersonDetailsResponse;
-use serde_json::Value;
+use lemmy_api_common::lemmy_db_views_moderator::structs::ModBanFromCommunityView;
+use lemmy_api_common::person::{GetPersonDetailsResponse, SaveUserSettings};
+use lemmy_api_common::sensitive::Sensitive;
+use lemmy_api_common::site::{GetModlogResponse, MyUserInfo};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
 use std::borrow::ToOwned;
-use tafkars::comment::{Comment, CommentData, MaybeReplies};
-use tafkars::listing::{Listing, ListingData};
-use tafkars::submission::{SortOrder, Submission, SubmissionData};
-use tafkars::subreddit;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use tafkars::api::ApiError;
+use tafkars::comment::{Comment, CommentData};
+use tafkars::fullname::{self, Fullname, ThingKind};
+use tafkars::listing::Listing;
+use tafkars::modlog::{ModAction, ModActionData};
+use tafkars::prefs::Prefs;
+use tafkars::submission::{
+    Edited, Media, MediaEmbed, ModReport, SortOrder, Submission, SubmissionData, UserReport,
+};
+use tafkars::{subreddit, RedditThing};
 
-use crate::endpoints;
-use tafkars::subreddit::{AccountsActive, FilterTime, Subreddit, SubredditData};
-use tafkars::user::{User, UserData};
+use crate::server_config::ServerSideError;
+use crate::{endpoints, media, sidebar};
+use tafkars::subreddit::{AccountsActive, FilterTime, Rule, Rules, Subreddit, SubredditData};
+use tafkars::trophy::{Trophy, TrophyData, TrophyList, TrophyListData};
+use tafkars::user::{KarmaList, RelUser, SubredditKarma, User, UserData, UserList, UserListData};
+use tafkars::wiki::{WikiPage, WikiPageData};
 
 pub fn timestamp(time: chrono::NaiveDateTime) -> f64 {
     time.timestamp() as f64 // TODO: is this utc?
 }
 
+/// Links to Lemmy posts, comments, communities and users, absolute or as relative Markdown link targets
+fn link_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?P<origin>https?://[\w.-]+(?::\d+)?)?/(?P<type>post|comment|c|u)/(?P<target>[\w.@-]*\w)")
+            .expect("valid regex")
+    })
+}
+
+/// Federated mentions like `!community@instance` and `@user@instance`
+fn mention_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?m)(?P<pre>^|[^\w/\[@!.])(?P<kind>[!@])(?P<name>\w+)@(?P<instance>[\w-]+(?:\.[\w-]+)+)")
+            .expect("valid regex")
+    })
+}
+
+pub fn host(url: &str) -> &str {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    url.split('/').next().unwrap_or(url)
+}
+
+/// Find the absolute URL of a link match, relative links are only considered in Markdown link targets
+fn link_url(state: &endpoints::ResponseState, text: &str, link: &Captures) -> Option<String> {
+    let whole = link.get(0)?;
+    let origin = match link.name("origin") {
+        Some(origin) => origin.as_str(),
+        None if text[..whole.start()].ends_with("](") => {
+            state.config.lemmy_url.trim_end_matches('/')
+        }
+        None => return None,
+    };
+    Some(format!("{origin}/{}/{}", &link["type"], &link["target"]))
+}
+
+/// Can this link be turned into a proxy path without asking Lemmy?
+fn is_local_link(state: &endpoints::ResponseState, origin: &str, link: &Captures) -> bool {
+    match &link["type"] {
+        "comment" => false,
+        // remote actors can be viewed on any instance as /c/name@instance
+        "c" | "u" if link["target"].contains('@') => true,
+        _ => host(origin) == host(&state.config.lemmy_url),
+    }
+}
+
+/// Links in `text` that need Lemmy's ResolveObject to be turned into proxy paths
+pub fn links_to_resolve(state: &endpoints::ResponseState, text: &str) -> Vec<String> {
+    link_regex()
+        .captures_iter(text)
+        .filter_map(|link| {
+            let url = link_url(state, text, &link)?;
+            let origin = &url[..url.len() - link["type"].len() - link["target"].len() - 2];
+            (!is_local_link(state, origin, &link)).then_some(url)
+        })
+        .collect()
+}
+
+/// Proxy path for a link to a Lemmy object, if the client can navigate to it
+fn proxy_path(state: &endpoints::ResponseState, url: &str, link: &Captures) -> Option<String> {
+    let origin = &url[..url.len() - link["type"].len() - link["target"].len() - 2];
+    if !is_local_link(state, origin, link) {
+        return state.links.get(url).cloned();
+    }
+
+    let target = &link["target"];
+    match &link["type"] {
+        ty @ ("c" | "u") => {
+            let actor_id = match target.split_once('@') {
+                Some((name, instance)) => format!("https://{instance}/{ty}/{name}"),
+                None => format!("{origin}/{ty}/{target}"),
+            };
+            let name = state.escape_actor_id_str(&actor_id)?;
+            let prefix = if ty == "c" { "r" } else { "u" };
+            Some(format!("/{prefix}/{name}"))
+        }
+        _ => {
+            let id: i32 = target.parse().ok()?;
+            Some(format!("/comments/{}", id36(id)))
+        }
+    }
+}
+
+/// Apply `f` to the parts of `md` that are not code blocks or code spans
+fn map_outside_code(md: &str, f: impl Fn(&str) -> String) -> String {
+    let mut out = String::with_capacity(md.len());
+    let mut text = String::new();
+    let mut in_fence = false;
+
+    let flush = |text: &mut String, out: &mut String| {
+        for (i, part) in text.split('`').enumerate() {
+            if i > 0 {
+                out.push('`');
+            }
+            if i % 2 == 0 {
+                out.push_str(&f(part));
+            } else {
+                out.push_str(part);
+            }
+        }
+        text.clear();
+    };
+
+    // indented code blocks start after a blank line, blank lines don't end them
+    let mut prev_blank = true;
+    let mut in_indented = false;
+    for line in md.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let blank = trimmed.is_empty();
+        let indented = line.starts_with("    ") || line.starts_with('\t');
+        if !in_fence && indented && !blank && (prev_blank || in_indented) {
+            if !in_indented {
+                flush(&mut text, &mut out);
+            }
+            in_indented = true;
+            out.push_str(line);
+        } else if in_indented && blank {
+            out.push_str(line);
+        } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_indented = false;
+            if !in_fence {
+                flush(&mut text, &mut out);
+            }
+            in_fence = !in_fence;
+            out.push_str(line);
+        } else if in_fence {
+            out.push_str(line);
+        } else {
+            in_indented = false;
+            text.push_str(line);
+        }
+        prev_blank = blank;
+    }
+    flush(&mut text, &mut out);
+    out
+}
+
+/// Rewrite federated mentions and links to Lemmy objects in Markdown into paths the client can navigate:
+/// `!community@instance` and `https://instance/c/community` become `/r/community__instance`,
+/// users become `/u/name__instance` and posts and comments become permalinks.
+/// HTML is rendered from the rewritten Markdown, so it is rewritten as well.
+pub fn rewrite_content(state: &endpoints::ResponseState, md: &str) -> String {
+    map_outside_code(md, |text| {
+        let text = link_regex().replace_all(text, |link: &Captures| {
+            let before = &text[..link.get(0).map_or(0, |m| m.start())];
+            let path = link_url(state, text, link).and_then(|url| proxy_path(state, &url, link));
+            match path {
+                Some(path) if before.ends_with("](") => path,
+                // don't nest links in link texts or autolinks
+                _ if before.ends_with(['[', '<']) => link[0].to_owned(),
+                Some(path) => format!("[{}]({path})", &link[0]),
+                None => link[0].to_owned(),
+            }
+        });
+
+        mention_regex()
+            .replace_all(&text, |m: &Captures| {
+                let (ty, prefix) = if &m["kind"] == "!" {
+                    ("c", "r")
+                } else {
+                    ("u", "u")
+                };
+                let actor_id = format!("https://{}/{ty}/{}", &m["instance"], &m["name"]);
+                match state.escape_actor_id_str(&actor_id) {
+                    Some(name) => format!(
+                        "{}[{}{}@{}](/{prefix}/{name})",
+                        &m["pre"], &m["kind"], &m["name"], &m["instance"]
+                    ),
+                    None => m[0].to_owned(),
+                }
+            })
+            .into_owned()
+    })
+}
+
+/// Maximum length of the title slug in permalinks
+const MAX_SLUG_LEN: usize = 50;
+
+/// URL-friendly version of a post title, e.g. `what_is_lemmy` for "What is Lemmy?"
+pub fn title_slug(title: &str) -> String {
+    let mut slug = String::new();
+    for word in title.split(|c: char| !c.is_alphanumeric()) {
+        if word.is_empty() {
+            continue;
+        }
+        let word = word.to_lowercase();
+        if slug.is_empty() {
+            slug = word.chars().take(MAX_SLUG_LEN).collect();
+        } else if slug.chars().count() + 1 + word.chars().count() <= MAX_SLUG_LEN {
+            slug.push('_');
+            slug.push_str(&word);
+        } else {
+            break;
+        }
+    }
+    if slug.is_empty() {
+        "_".to_owned()
+    } else {
+        slug
+    }
+}
+
+pub fn post_permalink(subreddit: &str, post_id: i32, title: &str) -> String {
+    format!(
+        "/r/{subreddit}/comments/{}/{}/",
+        id36(post_id),
+        title_slug(title)
+    )
+}
+
+/// Base-36 ID of a Lemmy object, as used in fullnames and permalinks
+pub fn id36(id: i32) -> String {
+    fullname::to_base36(id as u64)
+}
+
+/// Fullname of a Lemmy object, like `t3_` followed by the base-36 post ID
+pub fn fullname(kind: ThingKind, id: i32) -> Fullname {
+    Fullname::new(kind, id as u64)
+}
+
+/// The Lemmy ID of a thing, `None` if it is too large to be one
+pub fn lemmy_id(id: u64) -> Option<i32> {
+    i32::try_from(id).ok()
+}
+
+/// Distinguish content by admins and by moderators of the community it was posted in,
+/// like Lemmy shows badges next to their names
+fn distinction(
+    state: &endpoints::ResponseState,
+    community_id: CommunityId,
+    creator_id: PersonId,
+    creator_is_admin: bool,
+) -> Option<String> {
+    if creator_is_admin {
+        Some("admin".to_owned())
+    } else if state.moderators.contains(&(community_id, creator_id)) {
+        Some("moderator".to_owned())
+    } else {
+        None
+    }
+}
+
+/// Fields about the removal or approval of a thing, only shown to moderators
+#[derive(Default)]
+struct Moderation {
+    removed: Option<bool>,
+    approved: Option<bool>,
+    banned_by: Option<String>,
+    banned_at_utc: Option<f64>,
+    removal_reason: Option<String>,
+    approved_by: Option<String>,
+    approved_at_utc: Option<f64>,
+}
+
+/// Name shown as `banned_by` when the modlog doesn't say who removed something
+const UNKNOWN_MODERATOR: &str = "[unknown]";
+
+fn moderation(
+    state: &endpoints::ResponseState,
+    fullname: Fullname,
+    removed: bool,
+    can_mod: bool,
+) -> Moderation {
+    if !can_mod {
+        return Moderation::default();
+    }
+    // the modlog may be out of date or not go back far enough
+    let removal = state
+        .removals
+        .get(&fullname)
+        .filter(|r| r.removed == removed);
+
+    if removed {
+        Moderation {
+            removed: Some(true),
+            approved: Some(false),
+            banned_by: Some(
+                removal
+                    .and_then(|r| r.moderator.clone())
+                    .unwrap_or(UNKNOWN_MODERATOR.to_owned()),
+            ),
+            banned_at_utc: removal.map(|r| r.when),
+            removal_reason: removal.and_then(|r| r.reason.clone()),
+            ..Default::default()
+        }
+    } else {
+        Moderation {
+            removed: Some(false),
+            approved: Some(removal.is_some()),
+            approved_by: removal.and_then(|r| r.moderator.clone()),
+            approved_at_utc: removal.map(|r| r.when),
+            ..Default::default()
+        }
+    }
+}
+
 pub fn posts(state: &endpoints::ResponseState, posts: Vec<PostView>) -> Listing<Submission> {
     let posts = posts.into_iter().map(|p| post(state, p)).collect();
 
-    Listing {
-        data: ListingData {
-            modhash: Some("c2swiur5ry66d67eca991e911ebb57b824a27f0d9ad1264bf6".to_string()),
-            dist: Some(1),
-            after: None,
-            before: None,
-            children: posts,
-        },
-    }
+    let mut listing = Listing::new(posts);
+    listing.data.modhash = state.modhash.clone();
+    listing.data.dist = Some(1);
+    listing
 }
 
 pub fn post(state: &endpoints::ResponseState, pv: PostView) -> Submission {
     let p = pv.post;
+    let distinguished = distinction(state, p.community_id, p.creator_id, pv.creator.admin);
+    let can_mod_post = state.can_mod(p.community_id);
+    let moderation = moderation(
+        state,
+        fullname(ThingKind::Link, p.id.0),
+        p.removed,
+        can_mod_post,
+    );
     let community_id = p.community_id.0;
     let post_id = p.id;
     let subreddit = state
         .escape_actor_id(&pv.community.actor_id)
         .unwrap_or("invalid".to_owned());
-    let thumbnail = p
-        .thumbnail_url
-        .map(|u| u.to_string())
-        .unwrap_or("self".to_owned());
+    let url = p.url.map(|u| u.to_string());
+    let thumbnail_url = p.thumbnail_url.map(|u| u.to_string());
+    let thumbnail = match (&thumbnail_url, url.as_deref()) {
+        _ if p.nsfw => "nsfw".to_owned(),
+        (Some(thumbnail_url), _) => thumbnail_url.clone(),
+        (None, None) => "self".to_owned(),
+        (None, Some(url)) if media::is_image(url) => "image".to_owned(),
+        (None, Some(_)) => "default".to_owned(),
+    };
+    let domain = url
+        .as_deref()
+        .and_then(media::domain)
+        .unwrap_or(format!("self.{subreddit}"));
+
+    let embed_video_url = p.embed_video_url.map(|u| u.to_string());
+    let post_hint = media::post_hint(url.as_deref(), embed_video_url.is_some());
+    let image_url = url.as_deref().filter(|u| media::is_image(u));
+    let preview = image_url
+        .or(thumbnail_url.as_deref())
+        .map(|u| media::preview(u, image_url.is_some(), p.nsfw, state.res_config.raw_json));
+
+    let video_url = url.as_deref().filter(|u| media::is_video(u));
+    let is_video = video_url.is_some();
+    let secure = video_url
+        .or(embed_video_url.as_deref())
+        .is_some_and(|u| u.starts_with("https://"));
+    let embed_html = embed_video_url
+        .as_deref()
+        .map(|u| state.res_config.escape_html(media::embed_iframe(u)));
+    let media = match (video_url, url.as_deref()) {
+        (Some(video_url), _) => Some(Media {
+            reddit_video: Some(media::reddit_video(video_url)),
+            ..Default::default()
+        }),
+        (None, Some(url)) if embed_video_url.is_some() || p.embed_title.is_some() => {
+            let embed_url = embed_video_url.as_deref().unwrap_or(url);
+            Some(Media {
+                type_: media::domain(embed_url),
+                oembed: Some(media::oembed(
+                    embed_url,
+                    p.embed_title,
+                    p.embed_description,
+                    embed_html.clone(),
+                    thumbnail_url.clone(),
+                )),
+                ..Default::default()
+            })
+        }
+        _ => None,
+    };
+    let media_embed = embed_html
+        .map(|content| MediaEmbed {
+            content: Some(content),
+            width: Some(media::EMBED_WIDTH),
+            height: Some(media::EMBED_HEIGHT),
+            scrolling: Some(false),
+            media_domain_url: None,
+        })
+        .unwrap_or_default();
 
-    let edited = p
-        .updated
-        .map_or(Value::from(false), |ts| Value::from(timestamp(ts)));
+    let edited = Edited::from(p.updated.map(timestamp));
 
     let author = state
         .escape_actor_id(&pv.creator.actor_id)
         .unwrap_or("invalid".to_owned());
 
-    let permalink = format!("/r/{subreddit}/comments/{post_id}/permalink");
+    let selftext = rewrite_content(state, &p.body.unwrap_or("".to_owned()));
+    let selftext_html =
+        (!selftext.is_empty()).then(|| state.res_config.markdown_to_html(&selftext));
+
+    let permalink = post_permalink(&subreddit, post_id.0, &p.name);
     let created = timestamp(p.published);
 
     Submission {
         data: SubmissionData {
-            domain: Some(format!("self.{subreddit}")),
+            domain: Some(domain),
+            subreddit_name_prefixed: Some(format!("r/{subreddit}")),
+            subreddit_type: Some(
+                if pv.community.posting_restricted_to_mods {
+                    "restricted"
+                } else {
+                    "public"
+                }
+                .to_owned(),
+            ),
             subreddit,
-            selftext: p.body.unwrap_or("".to_owned()),
+            selftext_html,
+            selftext,
             likes: pv.my_vote.map(|v| v > 0),
-            id: post_id.to_string(),
+            id: id36(post_id.0),
             gilded: 0,
             archived: false,
             clicked: false,
             author,
+            author_fullname: Some(fullname(ThingKind::Account, p.creator_id.0)),
+            author_flair_type: Some("text".to_owned()),
+            link_flair_type: Some("text".to_owned()),
             score: pv.counts.score,
             over_18: p.nsfw,
             spoiler: false,
             hidden: false,
+            preview,
+            post_hint: Some(post_hint.to_owned()),
             num_comments: pv.counts.comments as u64,
             thumbnail,
-            subreddit_id: format!("t5_{community_id}"),
+            subreddit_id: fullname(ThingKind::Subreddit, community_id).to_string(),
             hide_score: false,
             edited,
             downs: pv.counts.downvotes,
@@ -79,23 +473,64 @@ pub fn post(state: &endpoints::ResponseState, pv: PostView) -> Submission {
             upvote_ratio: pv.counts.upvotes as f64 / pv.counts.downvotes as f64,
             saved: false,
             stickied: p.featured_community || p.featured_local,
-            is_self: p.url.is_none(),
+            is_self: url.is_none(),
             permalink,
             locked: p.locked,
-            name: format!("t3_{post_id}"),
+            name: Some(fullname(ThingKind::Link, post_id.0)),
             created,
             created_utc: created,
-            url: p.url.map(|u| u.to_string()),
+            url,
             quarantine: false,
             title: p.name,
             visited: false,
-            is_video: false,
-            can_mod_post: false,
+            is_video,
+            secure_media: media.clone().filter(|_| secure),
+            media,
+            secure_media_embed: if secure {
+                media_embed.clone()
+            } else {
+                Default::default()
+            },
+            media_embed,
+            distinguished,
+            can_mod_post,
+            removed: moderation.removed,
+            approved: moderation.approved,
+            banned_by: moderation.banned_by,
+            banned_at_utc: moderation.banned_at_utc,
+            removal_reason: moderation.removal_reason,
+            approved_by: moderation.approved_by,
+            approved_at_utc: moderation.approved_at_utc,
             ..Default::default()
         },
     }
 }
 
+/// Translate a post and the other posts sharing its URL, the oldest of which is the crosspost parent
+pub fn post_with_crossposts(
+    state: &endpoints::ResponseState,
+    pv: PostView,
+    cross_posts: Vec<PostView>,
+) -> Submission {
+    let published = pv.post.published;
+    let num_cross_posts = cross_posts.len() as u64;
+    let parent = cross_posts
+        .into_iter()
+        .filter(|cp| cp.post.published < published)
+        .min_by_key(|cp| cp.post.published);
+    // only the oldest post is anyone's crosspost parent, the others have no crossposts of their own
+    let num_crossposts = if parent.is_some() { 0 } else { num_cross_posts };
+
+    let mut submission = post(state, pv);
+    submission.data.num_crossposts = Some(num_crossposts);
+    if let Some(parent) = parent {
+        let parent = post(state, parent).data;
+        submission.data.crosspost_parent = parent.name;
+        submission.data.crosspost_parent_list = Some(vec![parent]);
+    }
+    submission
+}
+
 /// Insert `comment` into the comment tree at a position specified by the ancestor ids in `path`
 pub fn insert_at(comments: &mut Vec<Comment>, path: &[String], comment: Comment) {
     if path.is_empty() {
@@ -110,15 +545,7 @@ pub fn insert_at(comments: &mut Vec<Comment>, path: &[String], comment: Comment)
             .iter_mut()
             .find(|c| c.data.id.as_ref().unwrap() == parent_id)
         {
-            insert_at(
-                parent
-                    .data
-                    .replies
-                    .get_or_insert(Default::default())
-                    .replies(),
-                &path[1..],
-                comment,
-            );
+            insert_at(parent.data.replies_mut(), &path[1..], comment);
 
             return;
         }
@@ -138,29 +565,47 @@ pub fn comment_sort(order: SortOrder) -> Option<CommentSortType> {
     }
 }
 
+/// Has the logged in user blocked this person?
+fn is_blocked(state: &endpoints::ResponseState, person_id: PersonId) -> bool {
+    state
+        .my_user
+        .as_ref()
+        .is_some_and(|u| u.person_blocks.iter().any(|b| b.target.id == person_id))
+}
+
 pub fn comments(
     state: &endpoints::ResponseState,
     mut comments_list: Vec<CommentView>,
 ) -> Listing<Comment> {
+    // Lemmy doesn't always filter out comments by blocked users. Their replies go as well,
+    // instead of ending up at the top level as if their parent had been paginated away.
+    let blocked: HashSet<String> = comments_list
+        .iter()
+        .filter(|cv| cv.creator_blocked || is_blocked(state, cv.creator.id))
+        .map(|cv| cv.comment.id.0.to_string())
+        .collect();
+    comments_list.retain(|cv| !cv.comment.path.split('.').any(|id| blocked.contains(id)));
+
     let depth = |cv: &CommentView| cv.comment.path.matches('.').count();
     comments_list.sort_by_key(|cv| depth(cv)); // stable sort preserves Hot/Old/New/... sorting
 
     let mut comments: Vec<Comment> = Vec::new();
     for cv in comments_list.into_iter() {
-        let mut path: Vec<String> = cv.comment.path.split('.').map(|s| s.to_owned()).collect();
+        // comment IDs are base 36 in the translated comments
+        let mut path: Vec<String> = cv
+            .comment
+            .path
+            .split('.')
+            .map(|s| s.parse().map_or_else(|_| s.to_owned(), id36))
+            .collect();
         path.pop();
         insert_at(&mut comments, &path[1..], comment(state, cv))
     }
 
-    Listing {
-        data: ListingData {
-            modhash: Some("c2swiur5ry66d67eca991e911ebb57b824a27f0d9ad1264bf6".to_string()),
-            dist: Some(1),
-            after: None,
-            before: None,
-            children: comments,
-        },
-    }
+    let mut listing = Listing::new(comments);
+    listing.data.modhash = state.modhash.clone();
+    listing.data.dist = Some(1);
+    listing
 }
 
 pub fn comments_flat(
@@ -172,47 +617,53 @@ pub fn comments_flat(
         .map(|cv| comment(state, cv))
         .collect();
 
-    Listing {
-        data: ListingData {
-            modhash: Some("c2swiur5ry66d67eca991e911ebb57b824a27f0d9ad1264bf6".to_string()),
-            dist: Some(1),
-            after: None,
-            before: None,
-            children: comments,
-        },
-    }
+    let mut listing = Listing::new(comments);
+    listing.data.modhash = state.modhash.clone();
+    listing.data.dist = Some(1);
+    listing
 }
 
 pub fn comment(state: &endpoints::ResponseState, cv: CommentView) -> Comment {
     let c = cv.comment;
-    let post_id = cv.post.id.0.to_string();
+    // explicitly distinguished comments are always marked, even if we don't know the creator is a moderator
+    let distinguished = distinction(state, cv.community.id, c.creator_id, cv.creator.admin)
+        .or_else(|| c.distinguished.then(|| "moderator".to_owned()));
+    let is_submitter = c.creator_id == cv.post.creator_id;
+    let can_mod_post = state.can_mod(cv.community.id);
+    let moderation = moderation(
+        state,
+        fullname(ThingKind::Comment, c.id.0),
+        c.removed,
+        can_mod_post,
+    );
+    let post_id = cv.post.id.0;
     let subreddit = state
         .escape_actor_id(&cv.community.actor_id)
         .unwrap_or(cv.community.name);
-    let subreddit_id = cv.community.id.0.to_string();
+    let subreddit_id = fullname(ThingKind::Subreddit, cv.community.id.0).to_string();
 
     let author = state
         .escape_actor_id(&cv.creator.actor_id)
         .unwrap_or("invalid".to_owned());
-    let id = c.id.0.to_string();
+    let id = id36(c.id.0);
     let author_id = c.creator_id.0;
 
     let body = if c.deleted {
         "[deleted]".to_owned()
     } else {
-        c.content
+        rewrite_content(state, &c.content)
     };
 
     let body_html = state.res_config.markdown_to_html(&body);
 
     let path: Vec<&str> = c.path.split('.').collect();
-    let parent_id = *path.last().unwrap_or(&"wtf");
-    let parent_id = if parent_id == "0" {
-        format!("t3_{post_id}")
-    } else {
-        format!("t1_{parent_id}")
+    let parent_id = match path.iter().rev().nth(1).and_then(|id| id.parse().ok()) {
+        Some(0) | None => fullname(ThingKind::Link, post_id),
+        Some(parent_id) => fullname(ThingKind::Comment, parent_id),
     };
 
+    let link_permalink = post_permalink(&subreddit, post_id, &cv.post.name);
+    let permalink = format!("{link_permalink}{id}/");
     let created = timestamp(c.published);
 
     Comment {
@@ -222,17 +673,18 @@ pub fn comment(state: &endpoints::ResponseState, cv: CommentView) -> Comment {
             gilded: Some(0),
             archived: Some(false),
             author: Some(author),
-            author_fullname: Some(format!("t2_{author_id}")),
+            author_fullname: Some(fullname(ThingKind::Account, author_id)),
             subreddit: Some(subreddit.clone()),
             subreddit_id: Some(subreddit_id),
             subreddit_type: Some("public".to_string()),
-            can_mod_post: Some(false),
+            can_mod_post: Some(can_mod_post),
             created_utc: Some(created),
+            edited: Edited::from(c.updated.map(timestamp)),
             parent_id: Some(parent_id),
-            link_id: Some(post_id.clone()),
+            link_id: Some(fullname(ThingKind::Link, post_id)),
             score: Some(cv.counts.score as i32),
             body: Some(body),
-            name: Some(format!("t1_{id}")),
+            name: Some(fullname(ThingKind::Comment, c.id.0)),
             downs: Some(cv.counts.downvotes as i32),
             body_html: Some(body_html),
             stickied: Some(false),
@@ -240,13 +692,176 @@ pub fn comment(state: &endpoints::ResponseState, cv: CommentView) -> Comment {
             controversiality: Some(0),
             locked: Some(false),
             ups: Some(cv.counts.upvotes as i32),
-            replies: Some(MaybeReplies::Str("".to_owned())),
-            permalink: Some(format!("/r/{subreddit}/{post_id}/permalink/{id}")),
+            replies: None,
+            permalink: Some(permalink),
+            link_permalink: Some(link_permalink),
+            link_title: Some(cv.post.name),
+            distinguished,
+            is_submitter: Some(is_submitter),
+            removed: moderation.removed,
+            approved: moderation.approved,
+            banned_by: moderation.banned_by,
+            banned_at_utc: moderation.banned_at_utc,
+            removal_reason: moderation.removal_reason,
+            approved_by: moderation.approved_by,
+            approved_at_utc: moderation.approved_at_utc,
             ..Default::default()
         },
     }
 }
 
+/// Reports use an alias type for the creator of the reported content, which has the same fields as `PersonSafe`
+fn person_from_alias(alias: &impl Serialize) -> Option<PersonSafe> {
+    serde_json::to_value(alias)
+        .and_then(serde_json::from_value)
+        .ok()
+}
+
+/// Group reports of the same thing
+fn group_reports<R, K: PartialEq>(reports: Vec<R>, key: impl Fn(&R) -> K) -> Vec<Vec<R>> {
+    let mut groups: Vec<Vec<R>> = Vec::new();
+    for report in reports {
+        match groups.iter_mut().find(|g| key(&g[0]) == key(&report)) {
+            Some(group) => group.push(report),
+            None => groups.push(vec![report]),
+        }
+    }
+    groups
+}
+
+/// Reports of a thing by reporter and reason.
+/// User reports are counted by reason, reports by moderators keep the moderator's name.
+fn report_summary<'r>(
+    state: &endpoints::ResponseState,
+    community_id: CommunityId,
+    reports: impl IntoIterator<Item = (&'r PersonSafe, &'r str)>,
+) -> (Vec<UserReport>, Vec<ModReport>, Vec<String>) {
+    let mut user_reports: Vec<UserReport> = Vec::new();
+    let mut mod_reports = Vec::new();
+    let mut reasons = Vec::new();
+
+    for (reporter, reason) in reports {
+        if !reasons.iter().any(|r| r == reason) {
+            reasons.push(reason.to_owned());
+        }
+        if reporter.admin || state.moderators.contains(&(community_id, reporter.id)) {
+            let name = state
+                .escape_actor_id(&reporter.actor_id)
+                .unwrap_or(reporter.name.clone());
+            mod_reports.push(ModReport(reason.to_owned(), name));
+        } else if let Some(report) = user_reports.iter_mut().find(|r| r.0 == reason) {
+            report.1 += 1;
+        } else {
+            user_reports.push(UserReport(reason.to_owned(), 1, false, true));
+        }
+    }
+    (user_reports, mod_reports, reasons)
+}
+
+/// Reported posts and comments with their reports, the most recently reported first
+pub fn reports(
+    state: &endpoints::ResponseState,
+    post_reports: Vec<PostReportView>,
+    comment_reports: Vec<CommentReportView>,
+) -> Listing<RedditThing> {
+    let mut things: Vec<(f64, RedditThing)> = Vec::new();
+
+    for reports in group_reports(post_reports, |r| r.post.id) {
+        let latest = reports
+            .iter()
+            .map(|r| timestamp(r.post_report.published))
+            .fold(f64::MIN, f64::max);
+        let num_reports = reports.len();
+        let summary = report_summary(
+            state,
+            reports[0].community.id,
+            reports
+                .iter()
+                .map(|r| (&r.creator, r.post_report.reason.as_str())),
+        );
+        let (user_reports, mod_reports, reasons) = summary;
+
+        let Some(r) = reports.into_iter().next() else {
+            continue;
+        };
+        let Some(creator) = person_from_alias(&r.post_creator) else {
+            continue;
+        };
+        let pv = PostView {
+            post: r.post,
+            creator,
+            community: r.community,
+            creator_banned_from_community: r.creator_banned_from_community,
+            counts: r.counts,
+            subscribed: SubscribedType::NotSubscribed,
+            saved: false,
+            read: false,
+            creator_blocked: false,
+            my_vote: r.my_vote,
+            unread_comments: 0,
+        };
+
+        let mut data = post(state, pv).data;
+        data.num_reports = Some(num_reports as u64);
+        data.user_reports = Some(user_reports);
+        data.mod_reports = Some(mod_reports);
+        data.report_reasons = Some(reasons);
+        things.push((latest, RedditThing::Submission(data)));
+    }
+
+    for reports in group_reports(comment_reports, |r| r.comment.id) {
+        let latest = reports
+            .iter()
+            .map(|r| timestamp(r.comment_report.published))
+            .fold(f64::MIN, f64::max);
+        let num_reports = reports.len();
+        let summary = report_summary(
+            state,
+            reports[0].community.id,
+            reports
+                .iter()
+                .map(|r| (&r.creator, r.comment_report.reason.as_str())),
+        );
+        let (user_reports, mod_reports, reasons) = summary;
+
+        let Some(r) = reports.into_iter().next() else {
+            continue;
+        };
+        let Some(creator) = person_from_alias(&r.comment_creator) else {
+            continue;
+        };
+        let cv = CommentView {
+            comment: r.comment,
+            creator,
+            post: r.post,
+            community: r.community,
+            counts: r.counts,
+            creator_banned_from_community: r.creator_banned_from_community,
+            subscribed: SubscribedType::NotSubscribed,
+            saved: false,
+            creator_blocked: false,
+            my_vote: r.my_vote,
+        };
+
+        let mut data = comment(state, cv).data;
+        data.num_reports = Some(num_reports as i32);
+        data.user_reports = Some(user_reports);
+        data.mod_reports = Some(mod_reports);
+        data.report_reasons = Some(reasons);
+        data.ignore_reports = Some(false);
+        things.push((latest, RedditThing::Comment(data)));
+    }
+
+    things.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    let children: Vec<RedditThing> = things.into_iter().map(|(_, thing)| thing).collect();
+
+    let dist = Some(children.len() as i32);
+    let mut listing = Listing::new(children);
+    listing.data.modhash = state.modhash.clone();
+    listing.data.dist = dist;
+    listing
+}
+
 pub fn submission_sort(order: subreddit::SortOrder, time: Option<FilterTime>) -> Option<SortType> {
     use subreddit::SortOrder::*;
     use FilterTime::*;
@@ -268,33 +883,94 @@ pub fn submission_sort(order: subreddit::SortOrder, time: Option<FilterTime>) ->
 }
 
 pub fn community(state: &endpoints::ResponseState, cv: CommunityView) -> Subreddit {
-    let c = cv.community;
-    let id = c.id.0.to_string();
     let active = AccountsActive::Number(cv.counts.users_active_day as u64);
+    let mut data = community_data(state, cv.community);
+    data.accounts_active = Some(active.clone());
+    data.active_user_count = Some(active);
+    data.subscribers = Some(cv.counts.subscribers as u64);
+    Subreddit { data }
+}
+
+/// The subreddit fields that don't depend on community statistics
+fn community_data(state: &endpoints::ResponseState, c: CommunitySafe) -> SubredditData {
+    let id = id36(c.id.0);
     let name = state.escape_actor_id(&c.actor_id).unwrap_or(c.name);
-    let description = c.description.unwrap_or("".to_owned());
+    let description = rewrite_content(state, &c.description.unwrap_or("".to_owned()));
     let description_html = state.res_config.markdown_to_html(&description);
+    let public_description = sidebar::summary(&description);
+    let public_description_html = state.res_config.markdown_to_html(&public_description);
     let created = timestamp(c.published);
 
-    Subreddit {
-        data: SubredditData {
-            display_name: Some(name.clone()),
-            display_name_prefixed: Some(format!("r/{name}")),
-            header_img: c.banner.map(|x| x.to_string()),
-            title: Some(c.title),
-            id: Some(id.clone()),
-            accounts_active: Some(active.clone()),
-            active_user_count: Some(active),
-            subscribers: Some(cv.counts.subscribers as u64),
-            name: Some(format!("t5_{id}")),
-            description: Some(description.clone()), // TODO: should we include moderators and other stuff that lemmy shows in sidebar?
-            description_html: Some(description_html.clone()),
-            public_description: Some(description), // TODO: this should be a one liner, not the full sidebar
-            public_description_html: Some(description_html),
-            over18: Some(c.nsfw),
-            url: Some(format!("/r/{name}")),
-            created: Some(created),
-            created_utc: Some(created),
+    SubredditData {
+        display_name: Some(name.clone()),
+        display_name_prefixed: Some(format!("r/{name}")),
+        header_img: c.banner.map(|x| x.to_string()),
+        title: Some(c.title),
+        id: Some(id),
+        name: Some(fullname(ThingKind::Subreddit, c.id.0)),
+        description: Some(description.clone()), // TODO: should we include moderators and other stuff that lemmy shows in sidebar?
+        description_html: Some(description_html.clone()),
+        public_description: Some(public_description),
+        public_description_html: Some(public_description_html),
+        over18: Some(c.nsfw),
+        user_is_banned: state
+            .my_user
+            .is_some()
+            .then(|| state.banned_from.contains(&c.id)),
+        url: Some(format!("/r/{name}")),
+        created: Some(created),
+        created_utc: Some(created),
+        ..Default::default()
+    }
+}
+
+/// The rules of a community, parsed from its sidebar.
+/// The rules of the instance, parsed from the site sidebar, can be chosen as report reasons everywhere.
+pub fn community_rules(
+    state: &endpoints::ResponseState,
+    cv: CommunityView,
+    site_sidebar: Option<&str>,
+) -> Rules {
+    let c = cv.community;
+    let description = rewrite_content(state, &c.description.unwrap_or("".to_owned()));
+    let created = timestamp(c.published);
+
+    let rules = sidebar::rules(&description)
+        .into_iter()
+        .enumerate()
+        .map(|(i, rule)| Rule {
+            kind: "all".to_owned(),
+            description_html: (!rule.description.is_empty())
+                .then(|| state.res_config.markdown_to_html(&rule.description)),
+            description: rule.description,
+            violation_reason: rule.short_name.clone(),
+            short_name: rule.short_name,
+            created_utc: created,
+            priority: i as u32,
+        })
+        .collect();
+
+    Rules {
+        rules,
+        site_rules: sidebar::rules(site_sidebar.unwrap_or_default())
+            .into_iter()
+            .map(|rule| rule.short_name)
+            .collect(),
+    }
+}
+
+/// The full community sidebar, served as the wiki index page
+pub fn community_wiki(state: &endpoints::ResponseState, cv: CommunityView) -> WikiPage {
+    let c = cv.community;
+    let content_md = rewrite_content(state, &c.description.unwrap_or("".to_owned()));
+    let content_html = state.res_config.markdown_to_html(&content_md);
+
+    WikiPage {
+        data: WikiPageData {
+            content_md,
+            content_html,
+            may_revise: false,
+            revision_date: Some(timestamp(c.updated.unwrap_or(c.published))),
             ..Default::default()
         },
     }
@@ -313,7 +989,7 @@ pub fn user(state: &endpoints::ResponseState, user: GetPersonDetailsResponse) ->
     User {
         data: UserData {
             is_friend: Some(false),
-            id: Some(p.id.0.to_string()),
+            id: Some(id36(p.id.0)),
             over_18: Some(false),
             is_gold: Some(false),
             is_mod: Some(!user.moderates.is_empty()),
@@ -328,3 +1004,1021 @@ pub fn user(state: &endpoints::ResponseState, user: GetPersonDetailsResponse) ->
         },
     }
 }
+
+/// The logged in user, as returned by `/api/me.json`
+pub fn my_user(state: &endpoints::ResponseState, my_user: &MyUserInfo) -> User {
+    let view = &my_user.local_user_view;
+    let p = &view.person;
+    let username = state
+        .escape_actor_id(&p.actor_id)
+        .unwrap_or_else(|| p.name.clone());
+    let created = timestamp(p.published);
+
+    let comment_karma = view.counts.comment_score as i32;
+    let post_karma = view.counts.post_score as i32;
+
+    User {
+        data: UserData {
+            id: Some(id36(p.id.0)),
+            name: Some(username),
+            over_18: Some(view.local_user.show_nsfw),
+            is_gold: Some(false),
+            is_mod: Some(!my_user.moderates.is_empty()),
+            is_employee: Some(p.admin),
+            is_suspended: Some(p.banned),
+            has_verified_email: Some(view.local_user.email_verified),
+            link_karma: Some(post_karma),
+            comment_karma: Some(comment_karma),
+            total_karma: Some(post_karma + comment_karma),
+            created: Some(created),
+            created_utc: Some(created),
+            modhash: state.modhash.clone(),
+            ..Default::default()
+        },
+    }
+}
+
+/// The Lemmy modlog entries containing moderator actions of a reddit action type
+pub fn modlog_action_type(action: &str) -> Option<ModlogActionType> {
+    use ModlogActionType::*;
+    match action {
+        "removelink" | "approvelink" => Some(ModRemovePost),
+        "lock" | "unlock" => Some(ModLockPost),
+        "sticky" | "unsticky" => Some(ModFeaturePost),
+        "removecomment" | "approvecomment" => Some(ModRemoveComment),
+        "banuser" | "unbanuser" => Some(ModBanFromCommunity),
+        "addmoderator" | "removemoderator" => Some(ModAddCommunity),
+        _ => None,
+    }
+}
+
+/// The fields all moderator actions have in common.
+/// `table` is the name of the Lemmy modlog table, to make IDs unique.
+fn mod_action(
+    state: &endpoints::ResponseState,
+    table: &str,
+    id: i32,
+    action: &str,
+    moderator: Option<PersonSafe>,
+    when: chrono::NaiveDateTime,
+    community: &CommunitySafe,
+) -> ModActionData {
+    let subreddit = state
+        .escape_actor_id(&community.actor_id)
+        .unwrap_or(community.name.clone());
+    ModActionData {
+        id: format!("ModAction_{table}_{id}"),
+        action: action.to_owned(),
+        mod_: moderator
+            .as_ref()
+            .and_then(|m| state.escape_actor_id(&m.actor_id))
+            .unwrap_or(UNKNOWN_MODERATOR.to_owned()),
+        mod_id36: moderator.map(|m| id36(m.id.0)),
+        created_utc: timestamp(when),
+        subreddit_name_prefixed: format!("r/{subreddit}"),
+        subreddit,
+        sr_id36: id36(community.id.0),
+        ..Default::default()
+    }
+}
+
+/// Set the target of a moderator action to a post
+fn post_target(
+    state: &endpoints::ResponseState,
+    data: &mut ModActionData,
+    post: Post,
+    community: &CommunitySafe,
+) {
+    let subreddit = state
+        .escape_actor_id(&community.actor_id)
+        .unwrap_or(community.name.clone());
+    data.target_fullname = Some(fullname(ThingKind::Link, post.id.0));
+    data.target_permalink = Some(post_permalink(&subreddit, post.id.0, &post.name));
+    data.target_title = Some(post.name);
+    data.target_body = post.body;
+}
+
+/// The time of the oldest action in a list of a modlog page that is full, older actions of the list are on later pages
+fn modlog_horizon<T>(
+    list: &[T],
+    limit: i64,
+    when: impl Fn(&T) -> chrono::NaiveDateTime,
+) -> Option<f64> {
+    if (list.len() as i64) < limit {
+        return None;
+    }
+    list.iter().map(when).min().map(timestamp)
+}
+
+/// Flatten a page of Lemmy's modlog into moderator actions, keeping only `action`s taken by `mods`.
+/// Lemmy pages each type of action separately, so actions older than the returned time
+/// can still be missing; it is `None` if the page has all remaining actions.
+pub fn mod_actions(
+    state: &endpoints::ResponseState,
+    modlog: GetModlogResponse,
+    action: Option<&str>,
+    mods: Option<&[String]>,
+    limit: i64,
+) -> (Vec<ModActionData>, Option<f64>) {
+    let horizon = [
+        modlog_horizon(&modlog.removed_posts, limit, |v| v.mod_remove_post.when_),
+        modlog_horizon(&modlog.locked_posts, limit, |v| v.mod_lock_post.when_),
+        modlog_horizon(&modlog.featured_posts, limit, |v| v.mod_feature_post.when_),
+        modlog_horizon(&modlog.removed_comments, limit, |v| {
+            v.mod_remove_comment.when_
+        }),
+        modlog_horizon(&modlog.banned_from_community, limit, |v| {
+            v.mod_ban_from_community.when_
+        }),
+        modlog_horizon(&modlog.added_to_community, limit, |v| {
+            v.mod_add_community.when_
+        }),
+    ]
+    .into_iter()
+    .flatten()
+    .max_by(f64::total_cmp);
+
+    let mut actions = Vec::new();
+
+    for v in modlog.removed_posts {
+        let a = v.mod_remove_post;
+        let name = if a.removed.unwrap_or(true) {
+            "removelink"
+        } else {
+            "approvelink"
+        };
+        let mut data = mod_action(
+            state,
+            "ModRemovePost",
+            a.id,
+            name,
+            v.moderator,
+            a.when_,
+            &v.community,
+        );
+        data.details = a.reason.clone();
+        data.description = a.reason;
+        post_target(state, &mut data, v.post, &v.community);
+        actions.push(data);
+    }
+
+    for v in modlog.locked_posts {
+        let a = v.mod_lock_post;
+        let name = if a.locked.unwrap_or(true) {
+            "lock"
+        } else {
+            "unlock"
+        };
+        let mut data = mod_action(
+            state,
+            "ModLockPost",
+            a.id,
+            name,
+            v.moderator,
+            a.when_,
+            &v.community,
+        );
+        post_target(state, &mut data, v.post, &v.community);
+        actions.push(data);
+    }
+
+    for v in modlog.featured_posts {
+        let a = v.mod_feature_post;
+        let name = if a.featured { "sticky" } else { "unsticky" };
+        let mut data = mod_action(
+            state,
+            "ModFeaturePost",
+            a.id,
+            name,
+            v.moderator,
+            a.when_,
+            &v.community,
+        );
+        if !a.is_featured_community {
+            data.details = Some("featured on the local front page".to_owned());
+        }
+        post_target(state, &mut data, v.post, &v.community);
+        actions.push(data);
+    }
+
+    for v in modlog.removed_comments {
+        let a = v.mod_remove_comment;
+        let name = if a.removed.unwrap_or(true) {
+            "removecomment"
+        } else {
+            "approvecomment"
+        };
+        let mut data = mod_action(
+            state,
+            "ModRemoveComment",
+            a.id,
+            name,
+            v.moderator,
+            a.when_,
+            &v.community,
+        );
+        let subreddit = data.subreddit.clone();
+        data.details = a.reason.clone();
+        data.description = a.reason;
+        data.target_fullname = Some(fullname(ThingKind::Comment, v.comment.id.0));
+        data.target_author = state.escape_actor_id(&v.commenter.actor_id);
+        data.target_body = Some(v.comment.content);
+        data.target_permalink = Some(format!(
+            "{}{}/",
+            post_permalink(&subreddit, v.post.id.0, &v.post.name),
+            id36(v.comment.id.0)
+        ));
+        data.target_title = Some(v.post.name);
+        actions.push(data);
+    }
+
+    for v in modlog.banned_from_community {
+        let a = v.mod_ban_from_community;
+        let name = if a.banned.unwrap_or(true) {
+            "banuser"
+        } else {
+            "unbanuser"
+        };
+        let mut data = mod_action(
+            state,
+            "ModBanFromCommunity",
+            a.id,
+            name,
+            v.moderator,
+            a.when_,
+            &v.community,
+        );
+        if a.banned.unwrap_or(true) {
+            data.details = Some(match a.expires {
+                Some(expires) => format!("{} days", (expires - a.when_).num_days()),
+                None => "permanent".to_owned(),
+            });
+        }
+        data.description = a.reason;
+        data.target_fullname = Some(fullname(ThingKind::Account, v.banned_person.id.0));
+        data.target_author = state.escape_actor_id(&v.banned_person.actor_id);
+        actions.push(data);
+    }
+
+    for v in modlog.added_to_community {
+        let a = v.mod_add_community;
+        let name = if a.removed.unwrap_or(false) {
+            "removemoderator"
+        } else {
+            "addmoderator"
+        };
+        let mut data = mod_action(
+            state,
+            "ModAddCommunity",
+            a.id,
+            name,
+            v.moderator,
+            a.when_,
+            &v.community,
+        );
+        data.target_fullname = Some(fullname(ThingKind::Account, v.modded_person.id.0));
+        data.target_author = state.escape_actor_id(&v.modded_person.actor_id);
+        actions.push(data);
+    }
+
+    if let Some(action) = action {
+        actions.retain(|a| a.action == action);
+    }
+    if let Some(mods) = mods {
+        actions.retain(|a| mods.contains(&a.mod_));
+    }
+    (actions, horizon)
+}
+
+/// A page of at most `limit` moderator actions `after` or `before` the action with that ID.
+/// `actions` are sorted newest first, `complete` if there are no older ones.
+pub fn mod_log(
+    state: &endpoints::ResponseState,
+    actions: Vec<ModActionData>,
+    complete: bool,
+    after: Option<&str>,
+    before: Option<&str>,
+    limit: usize,
+) -> Listing<ModAction> {
+    let position = |id: &str| actions.iter().position(|a| a.id == id);
+    let (start, end) = match (after, before) {
+        (Some(after), _) => {
+            let start = position(after).map_or(actions.len(), |i| i + 1);
+            (start, (start + limit).min(actions.len()))
+        }
+        (None, Some(before)) => {
+            let end = position(before).unwrap_or(0);
+            (end.saturating_sub(limit), end)
+        }
+        (None, None) => (0, limit.min(actions.len())),
+    };
+    let has_more = end < actions.len() || !complete;
+
+    let children: Vec<ModAction> = actions
+        .into_iter()
+        .skip(start)
+        .take(end - start)
+        .map(|data| ModAction { data })
+        .collect();
+    let after = children
+        .last()
+        .filter(|_| has_more)
+        .map(|a| a.data.id.clone());
+    let before = children
+        .first()
+        .filter(|_| start > 0)
+        .map(|a| a.data.id.clone());
+
+    let dist = Some(children.len() as i32);
+    let mut listing = Listing::new(children);
+    listing.data.modhash = state.modhash.clone();
+    listing.data.dist = dist;
+    listing.data.after = after;
+    listing.data.before = before;
+    listing
+}
+
+/// The banned users of a community, from their latest bans
+pub fn banned_users(
+    state: &endpoints::ResponseState,
+    bans: Vec<ModBanFromCommunityView>,
+) -> UserList {
+    let now = chrono::Utc::now().naive_utc();
+    let children = bans
+        .into_iter()
+        .map(|b| {
+            let ban = b.mod_ban_from_community;
+            RelUser {
+                date: timestamp(ban.when_),
+                rel_id: Some(format!("rb_{}", ban.id)),
+                name: state
+                    .escape_actor_id(&b.banned_person.actor_id)
+                    .unwrap_or(b.banned_person.name),
+                id: fullname(ThingKind::Account, b.banned_person.id.0),
+                // round up, a ban that expires in an hour still has a day left
+                days_left: ban
+                    .expires
+                    .map(|expires| ((expires - now).num_hours().max(0) as u32 + 23) / 24),
+                note: ban.reason,
+            }
+        })
+        .collect();
+
+    UserList {
+        data: UserListData {
+            children,
+            after: None,
+            before: None,
+        },
+    }
+}
+
+/// The users blocked by the logged in user
+pub fn blocked_users(state: &endpoints::ResponseState, my_user: &MyUserInfo) -> UserList {
+    let children = my_user
+        .person_blocks
+        .iter()
+        .map(|b| RelUser {
+            // Lemmy doesn't tell when a user was blocked
+            date: 0.0,
+            rel_id: None,
+            name: state
+                .escape_actor_id(&b.target.actor_id)
+                .unwrap_or(b.target.name.clone()),
+            id: fullname(ThingKind::Account, b.target.id.0),
+            days_left: None,
+            note: None,
+        })
+        .collect();
+
+    UserList {
+        data: UserListData {
+            children,
+            after: None,
+            before: None,
+        },
+    }
+}
+
+/// The communities blocked by the logged in user
+pub fn blocked_communities(
+    state: &endpoints::ResponseState,
+    my_user: &MyUserInfo,
+) -> Listing<Subreddit> {
+    let communities = my_user
+        .community_blocks
+        .iter()
+        .map(|b| Subreddit {
+            data: community_data(state, b.community.clone()),
+        })
+        .collect();
+    Listing::new(communities)
+}
+
+/// Reddit preferences together with the Lemmy settings reddit doesn't have
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct LemmyPrefs {
+    #[serde(flatten)]
+    pub prefs: Prefs,
+    /// Show content by bot accounts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_bot_accounts: Option<bool>,
+    /// Show posts that have been read already
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_read_posts: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_avatars: Option<bool>,
+    /// Notify about new posts in subscribed communities
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_new_post_notifs: Option<bool>,
+    /// Name of the Lemmy UI theme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Default sort order of posts, like the `sort` of a subreddit listing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_link_sort: Option<subreddit::SortOrder>,
+    /// Time filter of the default sort order, like the `t` of a subreddit listing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_link_sort_t: Option<FilterTime>,
+    /// Default listing of the front page, as the index of Lemmy's `ListingType`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_listing_type: Option<i16>,
+    /// Mark the account as a bot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bot_account: Option<bool>,
+}
+
+/// Lemmy's `SortType` from the number the user settings store it as
+fn sort_type(n: i16) -> Option<SortType> {
+    use SortType::*;
+    Some(match n {
+        0 => Active,
+        1 => Hot,
+        2 => New,
+        3 => Old,
+        4 => TopDay,
+        5 => TopWeek,
+        6 => TopMonth,
+        7 => TopYear,
+        8 => TopAll,
+        9 => MostComments,
+        10 => NewComments,
+        _ => return None,
+    })
+}
+
+/// The number the user settings store a `SortType` as, the inverse of `sort_type`
+fn sort_type_number(sort: SortType) -> i16 {
+    use SortType::*;
+    match sort {
+        Active => 0,
+        Hot => 1,
+        New => 2,
+        Old => 3,
+        TopDay => 4,
+        TopWeek => 5,
+        TopMonth => 6,
+        TopYear => 7,
+        TopAll => 8,
+        MostComments => 9,
+        NewComments => 10,
+    }
+}
+
+/// The reddit sort order and time filter of a Lemmy sort type, the inverse of `submission_sort`.
+/// Old and NewComments have no reddit equivalent.
+fn link_sort(sort: SortType) -> Option<(subreddit::SortOrder, Option<FilterTime>)> {
+    use subreddit::SortOrder::*;
+    Some(match sort {
+        SortType::Active => (Rising, None),
+        SortType::Hot => (Hot, None),
+        SortType::New => (New, None),
+        SortType::MostComments => (Controversial, None),
+        SortType::TopDay => (Top, Some(FilterTime::Day)),
+        SortType::TopWeek => (Top, Some(FilterTime::Week)),
+        SortType::TopMonth => (Top, Some(FilterTime::Month)),
+        SortType::TopYear => (Top, Some(FilterTime::Year)),
+        SortType::TopAll => (Top, Some(FilterTime::All)),
+        SortType::Old | SortType::NewComments => return None,
+    })
+}
+
+/// The logged in user's settings as reddit preferences.
+/// Preferences Lemmy doesn't have are reported with the fixed values the proxy behaves like.
+pub fn prefs(my_user: &MyUserInfo) -> LemmyPrefs {
+    let settings = &my_user.local_user_view.local_user;
+    let default_sort = sort_type(settings.default_sort_type).and_then(link_sort);
+    let (default_link_sort, default_link_sort_t) = match default_sort {
+        Some((sort, t)) => (Some(sort), t),
+        None => (None, None),
+    };
+    LemmyPrefs {
+        prefs: Prefs {
+            over_18: Some(settings.show_nsfw),
+            search_include_over_18: Some(settings.show_nsfw),
+            label_nsfw: Some(true),
+            default_comment_sort: Some("confidence".to_owned()),
+            show_link_flair: Some(true),
+            show_flair: Some(true),
+            hide_ups: Some(!settings.show_scores),
+            hide_downs: Some(!settings.show_scores),
+            email_messages: Some(settings.send_notifications_to_email),
+            lang: Some(settings.interface_language.clone()),
+            nightmode: Some(false),
+            numsites: Some(10),
+            num_comments: Some(100),
+            // Lemmy doesn't hide anything by score
+            ..Default::default()
+        },
+        show_bot_accounts: Some(settings.show_bot_accounts),
+        show_read_posts: Some(settings.show_read_posts),
+        show_avatars: Some(settings.show_avatars),
+        show_new_post_notifs: Some(settings.show_new_post_notifs),
+        theme: Some(settings.theme.clone()),
+        default_link_sort,
+        default_link_sort_t,
+        default_listing_type: Some(settings.default_listing_type),
+        bot_account: Some(my_user.local_user_view.person.bot_account),
+    }
+}
+
+/// The Lemmy settings changed by a preferences update.
+/// Preferences Lemmy doesn't have are ignored, the response reports their fixed values.
+/// Lemmy has a single setting for hiding scores, so `hide_ups` and `hide_downs` can't differ.
+pub fn user_settings(
+    update: LemmyPrefs,
+    auth: Sensitive<String>,
+) -> Result<SaveUserSettings, ServerSideError> {
+    let prefs = update.prefs;
+    let show_scores = match (prefs.hide_ups, prefs.hide_downs) {
+        (Some(ups), Some(downs)) if ups != downs => {
+            return Err(ServerSideError::BadRequest(
+                "hide_ups and hide_downs can't differ".to_owned(),
+            ))
+        }
+        (ups, downs) => ups.or(downs).map(|hide| !hide),
+    };
+    Ok(SaveUserSettings {
+        show_nsfw: prefs.over_18,
+        show_scores,
+        send_notifications_to_email: prefs.email_messages,
+        interface_language: prefs.lang,
+        show_bot_accounts: update.show_bot_accounts,
+        show_read_posts: update.show_read_posts,
+        show_avatars: update.show_avatars,
+        show_new_post_notifs: update.show_new_post_notifs,
+        theme: update.theme,
+        default_sort_type: update
+            .default_link_sort
+            .and_then(|sort| submission_sort(sort, update.default_link_sort_t))
+            .map(sort_type_number),
+        default_listing_type: update.default_listing_type,
+        bot_account: update.bot_account,
+        auth,
+        ..Default::default()
+    })
+}
+
+/// Karma per community, from the scores of a user's posts and comments
+pub fn karma(
+    state: &endpoints::ResponseState,
+    posts: &[PostView],
+    comments: &[CommentView],
+) -> KarmaList {
+    let mut karma: Vec<SubredditKarma> = Vec::new();
+    let mut add = |community: &CommunitySafe, link_karma: i64, comment_karma: i64| {
+        let sr = state
+            .escape_actor_id(&community.actor_id)
+            .unwrap_or(community.name.clone());
+        match karma.iter_mut().find(|k| k.sr == sr) {
+            Some(k) => {
+                k.link_karma += link_karma;
+                k.comment_karma += comment_karma;
+            }
+            None => karma.push(SubredditKarma {
+                sr,
+                link_karma,
+                comment_karma,
+            }),
+        }
+    };
+    for pv in posts {
+        add(&pv.community, pv.counts.score, 0);
+    }
+    for cv in comments {
+        add(&cv.community, 0, cv.counts.score);
+    }
+
+    karma.sort_by_key(|k| -(k.link_karma + k.comment_karma));
+    KarmaList { data: karma }
+}
+
+const NUMBER_NAMES: [&str; 10] = [
+    "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten",
+];
+
+fn trophy(name: String, description: Option<String>, granted_at: Option<f64>) -> Trophy {
+    Trophy {
+        data: TrophyData {
+            name,
+            description,
+            granted_at,
+            ..Default::default()
+        },
+    }
+}
+
+/// Trophies for facts Lemmy knows about a user: account age, being an admin, moderator or bot
+pub fn trophies(user: &GetPersonDetailsResponse) -> TrophyList {
+    let p = &user.person_view.person;
+    let mut trophies = Vec::new();
+
+    let years = (chrono::Utc::now().naive_utc() - p.published).num_days() / 365;
+    if years >= 1 {
+        let name = match NUMBER_NAMES.get(years as usize - 1) {
+            Some(number) => format!("{number}-Year Club"),
+            None => format!("{years}-Year Club"),
+        };
+        let granted_at = p.published + chrono::Duration::days(years * 365);
+        trophies.push(trophy(name, None, Some(timestamp(granted_at))));
+    }
+    if p.admin {
+        trophies.push(trophy("Admin".to_owned(), None, None));
+    }
+    if !user.moderates.is_empty() {
+        let n = user.moderates.len();
+        let description = format!(
+            "Moderator of {n} communit{}",
+            if n == 1 { "y" } else { "ies" }
+        );
+        trophies.push(trophy("Moderator".to_owned(), Some(description), None));
+    }
+    if p.bot_account {
+        trophies.push(trophy("Bot Account".to_owned(), None, None));
+    }
+
+    TrophyList {
+        data: TrophyListData { trophies },
+    }
+}
+
+/// Translate a Lemmy error message like `user_already_exists` to the reddit error of a `json.errors` array
+pub fn api_error(error: &str) -> ApiError {
+    let (code, message, field) = match error {
+        "user_already_exists" => ("USERNAME_TAKEN", "that username is already taken", "user"),
+        "invalid_username" => ("BAD_USERNAME", "invalid user name", "user"),
+        "invalid_password" => (
+            "BAD_PASSWORD",
+            "password must be between 10 and 60 characters long",
+            "passwd",
+        ),
+        "passwords_dont_match" => ("BAD_PASSWORD_MATCH", "passwords do not match", "passwd2"),
+        "incorrect_login" | "password_incorrect" => ("WRONG_PASSWORD", "wrong password", "passwd"),
+        "captcha_incorrect" => ("BAD_CAPTCHA", "care to try these again?", "captcha"),
+        "email_required" => ("NO_EMAIL", "please enter an email address", "email"),
+        "email_already_exists" => (
+            "EMAIL_TAKEN",
+            "that email address is already in use",
+            "email",
+        ),
+        "registration_application_answer_required" => (
+            "APPLICATION_REQUIRED",
+            "this instance requires an application to sign up, please register on its website",
+            "answer",
+        ),
+        "registration_closed" => ("REGISTRATION_CLOSED", "registration is closed", ""),
+        "registration_application_is_pending" => (
+            "APPLICATION_PENDING",
+            "your registration application is awaiting approval",
+            "",
+        ),
+        "email_not_verified" => (
+            "EMAIL_NOT_VERIFIED",
+            "please verify your email address, check your inbox",
+            "",
+        ),
+        "rate_limit_error" => (
+            "RATELIMIT",
+            "you are doing that too much. try again later.",
+            "ratelimit",
+        ),
+        _ => {
+            return ApiError(error.to_uppercase(), error.replace('_', " "), None);
+        }
+    };
+    ApiError(
+        code.to_owned(),
+        message.to_owned(),
+        Some(field.to_owned()).filter(|f| !f.is_empty()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_config::GatewayConfig;
+    use std::collections::HashMap;
+
+    fn with_state<R>(f: impl FnOnce(&mut endpoints::ResponseState) -> R) -> R {
+        let app = endpoints::AppState::default();
+        let config = GatewayConfig {
+            lemmy_url: "https://lemmy.example".to_owned(),
+        };
+        let mut state = endpoints::ResponseState {
+            app: &app,
+            config: &config,
+            res_config: endpoints::ResponseConfig {
+                raw_json: false,
+                escape_names: true,
+                unescape_names: true,
+            },
+            auth: None,
+            modhash: None,
+            modhash_valid: true,
+            links: HashMap::new(),
+            moderators: HashSet::new(),
+            site: None,
+            my_user: None,
+            removals: HashMap::new(),
+            banned_from: HashSet::new(),
+        };
+        f(&mut state)
+    }
+
+    fn rewrite(md: &str) -> String {
+        with_state(|state| rewrite_content(state, md))
+    }
+
+    #[test]
+    fn map_outside_code_skips_code() {
+        let md = "a `b` c\n```\nd\n```\ne\n\n    f\ng";
+        assert_eq!(
+            map_outside_code(md, str::to_uppercase),
+            "A `b` C\n```\nd\n```\nE\n\n    f\nG"
+        );
+    }
+
+    #[test]
+    fn mention_regex_forms() {
+        let names: Vec<_> = mention_regex()
+            .captures_iter("!rust@lemmy.ml, @alice@example.com and bob@example.com")
+            .map(|m| format!("{}{}@{}", &m["kind"], &m["name"], &m["instance"]))
+            .collect();
+        assert_eq!(names, ["!rust@lemmy.ml", "@alice@example.com"]);
+    }
+
+    #[test]
+    fn link_regex_targets() {
+        let targets: Vec<_> = link_regex()
+            .captures_iter("https://lemmy.ml/c/rust@lemmy.world, /post/42.")
+            .map(|m| format!("{} {}", &m["type"], &m["target"]))
+            .collect();
+        assert_eq!(targets, ["c rust@lemmy.world", "post 42"]);
+    }
+
+    #[test]
+    fn rewrite_mentions() {
+        assert_eq!(
+            rewrite("see !rust@lemmy.ml and @alice@example.com"),
+            "see [!rust@lemmy.ml](/r/rust__lemmy_ml) and [@alice@example.com](/u/alice__example_com)"
+        );
+        assert_eq!(rewrite("mail bob@example.com"), "mail bob@example.com");
+    }
+
+    #[test]
+    fn rewrite_skips_mentions_in_code() {
+        let md = "`!rust@lemmy.ml`\n```\n@alice@example.com\n```\n";
+        assert_eq!(rewrite(md), md);
+    }
+
+    #[test]
+    fn rewrite_local_links() {
+        assert_eq!(
+            rewrite("https://lemmy.example/post/42"),
+            "[https://lemmy.example/post/42](/comments/16)"
+        );
+        assert_eq!(
+            rewrite("https://lemmy.example/c/rust@lemmy.ml"),
+            "[https://lemmy.example/c/rust@lemmy.ml](/r/rust__lemmy_ml)"
+        );
+    }
+
+    #[test]
+    fn rewrite_nested_links() {
+        assert_eq!(
+            rewrite("[Rust](https://lemmy.example/c/rust) [Rust](/c/rust)"),
+            "[Rust](/r/rust__lemmy_example) [Rust](/r/rust__lemmy_example)"
+        );
+        let md = "[https://lemmy.example/c/rust](https://example.com) [!rust@lemmy.ml](/c/rust)";
+        assert_eq!(
+            rewrite(md),
+            "[https://lemmy.example/c/rust](https://example.com) [!rust@lemmy.ml](/r/rust__lemmy_example)"
+        );
+        assert_eq!(rewrite("</c/rust>"), "</c/rust>");
+    }
+
+    #[test]
+    fn rewrite_unknown_instance_links() {
+        assert_eq!(
+            rewrite("https://other.example/post/5"),
+            "https://other.example/post/5"
+        );
+        with_state(|state| {
+            state.links.insert(
+                "https://lemmy.ml/post/5".to_owned(),
+                "/r/rust__lemmy_ml/comments/2/title/".to_owned(),
+            );
+            assert_eq!(
+                rewrite_content(state, "https://lemmy.ml/post/5"),
+                "[https://lemmy.ml/post/5](/r/rust__lemmy_ml/comments/2/title/)"
+            );
+        });
+    }
+
+    #[test]
+    fn links_to_resolve_skips_local() {
+        let md = "https://lemmy.example/comment/3 https://lemmy.example/post/4 \
+                  https://lemmy.ml/post/5 [x](/comment/7) /comment/8";
+        let urls = with_state(|state| links_to_resolve(state, md));
+        assert_eq!(
+            urls,
+            [
+                "https://lemmy.example/comment/3",
+                "https://lemmy.ml/post/5",
+                "https://lemmy.example/comment/7",
+            ]
+        );
+    }
+
+    fn modlog_post(id: i32) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "name": "Hello",
+            "creator_id": 1,
+            "community_id": 2,
+            "removed": false,
+            "locked": false,
+            "published": "2023-06-01T12:00:00",
+            "deleted": false,
+            "nsfw": false,
+            "ap_id": format!("https://lemmy.example/post/{id}"),
+            "local": true,
+            "language_id": 0,
+            "featured_community": false,
+            "featured_local": false,
+        })
+    }
+
+    fn modlog_community() -> serde_json::Value {
+        serde_json::json!({
+            "id": 2,
+            "name": "rust",
+            "title": "Rust",
+            "removed": false,
+            "published": "2023-01-01T00:00:00",
+            "deleted": false,
+            "nsfw": false,
+            "actor_id": "https://lemmy.example/c/rust",
+            "local": true,
+            "hidden": false,
+            "posting_restricted_to_mods": false,
+            "instance_id": 1,
+        })
+    }
+
+    #[test]
+    fn modlog_flattening() {
+        let empty = serde_json::json!([]);
+        let modlog: GetModlogResponse = serde_json::from_value(serde_json::json!({
+            "removed_posts": [{
+                "mod_remove_post": {
+                    "id": 7, "mod_person_id": 1, "post_id": 3, "reason": "spam",
+                    "removed": false, "when_": "2023-06-02T12:00:00",
+                },
+                "post": modlog_post(3),
+                "community": modlog_community(),
+            }],
+            "locked_posts": [{
+                "mod_lock_post": {
+                    "id": 4, "mod_person_id": 1, "post_id": 5, "locked": true,
+                    "when_": "2023-06-03T12:00:00",
+                },
+                "post": modlog_post(5),
+                "community": modlog_community(),
+            }],
+            "featured_posts": empty, "removed_comments": empty, "removed_communities": empty,
+            "banned_from_community": empty, "banned": empty, "added_to_community": empty,
+            "transferred_to_community": empty, "added": empty, "admin_purged_persons": empty,
+            "admin_purged_communities": empty, "admin_purged_posts": empty,
+            "admin_purged_comments": empty, "hidden_communities": empty,
+        }))
+        .unwrap();
+
+        let (actions, horizon) = with_state(|state| mod_actions(state, modlog, None, None, 1));
+        // both lists filled the page of one action, the older one can be followed by more
+        assert_eq!(
+            horizon,
+            Some(timestamp(
+                chrono::NaiveDate::from_ymd_opt(2023, 6, 3)
+                    .unwrap()
+                    .and_hms_opt(12, 0, 0)
+                    .unwrap()
+            ))
+        );
+        let actions: Vec<_> = actions
+            .iter()
+            .map(|a| (a.id.as_str(), a.action.as_str(), a.mod_.as_str()))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                (
+                    "ModAction_ModRemovePost_7",
+                    "approvelink",
+                    UNKNOWN_MODERATOR
+                ),
+                ("ModAction_ModLockPost_4", "lock", UNKNOWN_MODERATOR),
+            ]
+        );
+    }
+
+    #[test]
+    fn modlog_paging() {
+        let actions = || {
+            (1..=5)
+                .rev()
+                .map(|i| ModActionData {
+                    id: format!("ModAction_{i}"),
+                    created_utc: i as f64,
+                    ..Default::default()
+                })
+                .collect::<Vec<_>>()
+        };
+        let page = |complete, after, before| {
+            let log = with_state(|state| mod_log(state, actions(), complete, after, before, 2));
+            let ids: Vec<_> = log.data.children.into_iter().map(|a| a.data.id).collect();
+            (ids, log.data.after, log.data.before)
+        };
+        let id = |i: i32| Some(format!("ModAction_{i}"));
+
+        assert_eq!(
+            page(true, None, None),
+            (
+                vec!["ModAction_5".to_owned(), "ModAction_4".to_owned()],
+                id(4),
+                None
+            )
+        );
+        assert_eq!(
+            page(true, Some("ModAction_4"), None),
+            (
+                vec!["ModAction_3".to_owned(), "ModAction_2".to_owned()],
+                id(2),
+                id(3)
+            )
+        );
+        assert_eq!(
+            page(true, Some("ModAction_2"), None),
+            (vec!["ModAction_1".to_owned()], None, id(1))
+        );
+        assert_eq!(
+            page(false, Some("ModAction_2"), None),
+            (vec!["ModAction_1".to_owned()], id(1), id(1))
+        );
+        assert_eq!(
+            page(true, None, Some("ModAction_2")),
+            (
+                vec!["ModAction_4".to_owned(), "ModAction_3".to_owned()],
+                id(3),
+                id(4)
+            )
+        );
+    }
+
+    #[test]
+    fn sort_type_numbers() {
+        for n in 0..=10 {
+            assert_eq!(sort_type(n).map(sort_type_number), Some(n));
+        }
+        assert!(sort_type(11).is_none());
+    }
+
+    #[test]
+    fn hide_scores_prefs() {
+        let settings = |hide_ups, hide_downs| {
+            let update = LemmyPrefs {
+                prefs: Prefs {
+                    hide_ups,
+                    hide_downs,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            user_settings(update, Sensitive::new("jwt".to_owned())).map(|s| s.show_scores)
+        };
+        assert_eq!(settings(None, Some(true)).unwrap(), Some(false));
+        assert_eq!(settings(Some(false), Some(false)).unwrap(), Some(true));
+        assert_eq!(settings(None, None).unwrap(), None);
+        assert!(settings(Some(true), Some(false)).is_err());
+    }
+}
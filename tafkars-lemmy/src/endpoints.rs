@@ -1,24 +1,108 @@
-use actix_web::http::header::ContentType;
-use actix_web::{get, web, HttpRequest, HttpResponse};
-use lemmy_api_common::comment::{GetComments, GetCommentsResponse};
-use lemmy_api_common::community::{GetCommunity, GetCommunityResponse};
-use lemmy_api_common::lemmy_db_schema::newtypes::{DbUrl, PostId};
-use lemmy_api_common::lemmy_db_schema::ListingType;
-use lemmy_api_common::person::{GetPersonDetails, GetPersonDetailsResponse};
-use lemmy_api_common::post::{GetPost, GetPostResponse, GetPosts, GetPostsResponse};
-use reqwest::Client;
+use actix_multipart::Multipart;
+use actix_web::cookie::Cookie;
+use actix_web::dev::Payload;
+use actix_web::http::header::{self, ContentType};
+use actix_web::http::Method;
+use actix_web::{get, post, web, FromRequest, HttpMessage, HttpRequest, HttpResponse};
+use base64::Engine;
+use futures::future::LocalBoxFuture;
+use futures::{StreamExt, TryStreamExt};
+use lemmy_api_common::comment::{
+    CommentReportResponse, CommentResponse, CreateCommentReport, DistinguishComment, GetComment,
+    GetComments, GetCommentsResponse, ListCommentReports, ListCommentReportsResponse,
+    RemoveComment, ResolveCommentReport,
+};
+use lemmy_api_common::community::{
+    BanFromCommunity, BanFromCommunityResponse, BlockCommunity, BlockCommunityResponse,
+    GetCommunity, GetCommunityResponse,
+};
+use lemmy_api_common::lemmy_db_schema::newtypes::{
+    CommentId, CommunityId, DbUrl, PersonId, PostId,
+};
+use lemmy_api_common::lemmy_db_schema::{
+    ListingType, ModlogActionType, PostFeatureType, SearchType, SortType,
+};
+use lemmy_api_common::lemmy_db_views::structs::{CommentView, PostView};
+use lemmy_api_common::lemmy_db_views_actor::structs::CommunityModeratorView;
+use lemmy_api_common::lemmy_db_views_moderator::structs::ModBanFromCommunityView;
+use lemmy_api_common::person::{
+    BlockPerson, BlockPersonResponse, GetCaptcha, GetCaptchaResponse, GetPersonDetails,
+    GetPersonDetailsResponse, Login, LoginResponse, Register, SaveUserSettings,
+};
+use lemmy_api_common::post::{
+    CreatePostReport, EditPost, FeaturePost, GetPost, GetPostResponse, GetPosts, GetPostsResponse,
+    ListPostReports, ListPostReportsResponse, LockPost, PostReportResponse, PostResponse,
+    RemovePost, ResolvePostReport,
+};
+use lemmy_api_common::sensitive::Sensitive;
+use lemmy_api_common::site::{
+    GetModlog, GetModlogResponse, GetSite, GetSiteResponse, MyUserInfo, ResolveObject,
+    ResolveObjectResponse, Search, SearchResponse,
+};
+use rand::distributions::{Alphanumeric, DistString};
+use reqwest::{multipart, Client, RequestBuilder};
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tafkars::api::{self, Empty, JsonResponse, Things};
+use tafkars::fullname::{Fullname, Id36, ThingKind};
 use tafkars::listing::Listing;
-use tafkars::{submission, subreddit, user};
+use tafkars::modlog::{self, ModAction, ModActionData};
+use tafkars::{submission, subreddit, user, RedditThing};
 
 use crate::api_translation;
+use crate::markdown_render;
 use crate::server_config;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct AppState {
     pub http_client: Client,
+    /// Leases handed out by `/api/media/asset.json`, by upload key
+    pub upload_leases: Arc<Mutex<HashMap<String, UploadLease>>>,
+    /// Captcha images from Lemmy, by captcha UUID
+    pub captchas: Arc<Mutex<HashMap<String, CaptchaImage>>>,
+    /// Hosts of the instances Lemmy federates with, filled by [`ResponseState::known_instances`]
+    pub known_instances: Arc<Mutex<Option<KnownInstances>>>,
+    /// Proxy paths of links resolved by [`ResponseState::resolve_links`], by URL
+    pub resolved_links: Arc<Mutex<HashMap<String, ResolvedLink>>>,
+    /// Sessions of clients logged in through `/api/login`, by session cookie.
+    /// They are only kept in memory, so clients have to log in again after a restart or when they expire.
+    pub sessions: Arc<Mutex<HashMap<String, LoginSession>>>,
+}
+
+/// A cookie session, mapped to the Lemmy JWT of the user
+#[derive(Clone)]
+pub struct LoginSession {
+    pub jwt: Sensitive<String>,
+    /// Has to be sent as `X-Modhash` or `uh` with requests that change something
+    pub modhash: String,
+    pub expires: Instant,
+}
+
+pub struct ResolvedLink {
+    pub path: String,
+    pub expires: Instant,
+}
+
+pub struct KnownInstances {
+    pub hosts: HashSet<String>,
+    pub expires: Instant,
+}
+
+pub struct CaptchaImage {
+    pub png: Vec<u8>,
+    pub expires: Instant,
+}
+
+/// Permission to upload a file to pict-rs on behalf of a user
+pub struct UploadLease {
+    pub auth: Sensitive<String>,
+    pub filepath: String,
+    pub mimetype: String,
+    pub expires: Instant,
 }
 
 pub struct ResponseConfig {
@@ -32,8 +116,11 @@ pub struct ResponseConfig {
 
 impl ResponseConfig {
     pub fn markdown_to_html(&self, md_text: &str) -> String {
-        // TODO: fix <blockquote> tags
-        let html = markdown::to_html(md_text);
+        self.escape_html(markdown_render::to_html(md_text))
+    }
+
+    /// HTML-escape HTML in responses, unless `raw_json=1` was requested
+    pub fn escape_html(&self, html: String) -> String {
         if self.raw_json {
             html
         } else {
@@ -45,10 +132,91 @@ impl ResponseConfig {
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(web_root)
         .service(frontpage)
+        .service(duplicates)
+        .service(lemmy_post)
+        .service(distinguish)
+        .service(approve)
+        .service(remove)
+        .service(lock)
+        .service(unlock)
+        .service(set_subreddit_sticky)
+        .service(mark_nsfw)
+        .service(unmark_nsfw)
+        .service(report)
+        .service(ignore_reports)
+        .service(mod_log)
+        .service(banned)
+        .service(block_user)
+        .service(media_asset)
+        .service(media_upload)
+        .service(register)
+        .service(web::resource(["/api/login", "/api/login/{user}"]).route(web::post().to(login)))
+        .service(web::resource(["/api/me", "/api/me.json"]).route(web::get().to(me)))
+        .service(web::resource(["/api/info", "/api/info.json"]).route(web::get().to(info)))
+        .service(by_id)
+        .service(new_captcha)
+        .service(captcha)
+        .service(
+            web::resource(["/api/v1/me/karma", "/api/v1/me/karma.json"])
+                .route(web::get().to(karma)),
+        )
+        .service(
+            web::resource([
+                "/api/v1/user/{username}/trophies",
+                "/api/v1/user/{username}/trophies.json",
+                "/user/{username}/trophies{_:/?}.json",
+            ])
+            .route(web::get().to(trophies)),
+        )
+        .service(
+            web::resource(["/api/v1/me/prefs", "/api/v1/me/prefs.json"])
+                .route(web::get().to(prefs))
+                .route(web::patch().to(update_prefs)),
+        )
+        .service(
+            web::resource("/api/filter/user/{username}/f/{filter}")
+                .route(web::get().to(filtered_communities)),
+        )
+        .service(
+            web::resource("/api/filter/user/{username}/f/{filter}/r/{subreddit}")
+                .route(web::put().to(filter_community))
+                .route(web::delete().to(unfilter_community)),
+        )
+        .service(
+            web::resource([
+                "/prefs/blocked{_:/?}.json",
+                "/api/v1/me/blocked",
+                "/api/v1/me/blocked.json",
+            ])
+            .route(web::get().to(blocked)),
+        )
+        .service(
+            web::resource(["/api/friend", "/r/{subreddit}/api/friend"])
+                .route(web::post().to(friend)),
+        )
+        .service(
+            web::resource(["/api/unfriend", "/r/{subreddit}/api/unfriend"])
+                .route(web::post().to(unfriend)),
+        )
+        .service(
+            web::resource([
+                "/r/{subreddit}/about/reports{_:/?}.json",
+                "/r/{subreddit}/about/modqueue{_:/?}.json",
+            ])
+            .route(web::get().to(mod_queue)),
+        )
         .service(user_about)
         .service(user_submitted)
         .service(user_comments)
         .service(community_about)
+        .service(community_rules)
+        .service(
+            web::resource([
+                "/r/{subreddit}/wiki{_:/?}.json",
+                "/r/{subreddit}/wiki/index{_:/?}.json",
+            ])
+            .route(web::get().to(community_wiki)),
+        )
         .service(
             web::resource([
                 "/r/{subreddit}{_:/?}.json",
@@ -59,16 +227,91 @@ pub fn config(cfg: &mut web::ServiceConfig) {
         .service(
             web::resource([
                 "/comments/{post_id}{_:/?}.json",
+                "/comments/{post_id}/{link_title}{_:/?}.json",
+                "/comments/{post_id}/{link_title}/{comment_id}{_:/?}.json",
+                "/r/{subreddit}/comments/{post_id}{_:/?}.json",
                 "/r/{subreddit}/comments/{post_id}/{link_title}{_:/?}.json",
+                "/r/{subreddit}/comments/{post_id}/{link_title}/{comment_id}{_:/?}.json",
             ])
             .route(web::get().to(comments_for_post)),
         );
 }
 
+/// Maximum number of links to foreign posts and comments resolved for a single response
+const MAX_RESOLVED_LINKS: usize = 10;
+/// Number of posts per page of r/all and the front page, like on reddit
+const POSTS_LIMIT: i64 = 25;
+/// Number of pages fetched to fill up a page of posts that lost posts in blocked communities
+const MAX_FILTERED_PAGES: i64 = 5;
+/// Number of modlog entries searched for removals and approvals, the maximum Lemmy allows
+const MODLOG_LIMIT: i64 = 50;
+/// Number of unresolved reports listed or searched, the maximum Lemmy allows
+const REPORTS_LIMIT: i64 = 50;
+/// Number of posts and comments per page of a user's content, the maximum Lemmy allows
+const USER_CONTENT_LIMIT: i64 = 50;
+/// Number of pages of a user's content that are added up for their karma breakdown
+const MAX_KARMA_PAGES: i64 = 10;
+/// How long upload leases are valid
+const UPLOAD_LEASE_DURATION: Duration = Duration::from_secs(10 * 60);
+/// Maximum number of things looked up by `/api/info?id=` and `/by_id`, like on reddit
+const MAX_INFO_IDS: usize = 100;
+/// Number of things looked up at the same time
+const MAX_CONCURRENT_LOOKUPS: usize = 10;
+/// Maximum number of posts found by `/api/info?url=`
+const INFO_URL_LIMIT: i64 = 50;
+/// Name of the cookie identifying sessions created by `/api/login`
+const SESSION_COOKIE: &str = "reddit_session";
+/// How long cookie sessions are valid, clients have to log in again afterwards
+const SESSION_DURATION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// How long the instances Lemmy federates with are cached
+const KNOWN_INSTANCES_DURATION: Duration = Duration::from_secs(60 * 60);
+/// How long the proxy paths of resolved links are cached
+const RESOLVED_LINKS_DURATION: Duration = Duration::from_secs(60 * 60);
+/// How long Lemmy accepts answers to a captcha
+const CAPTCHA_DURATION: Duration = Duration::from_secs(10 * 60);
+/// Maximum size of an upload request body, all form fields together
+const MAX_UPLOAD_SIZE: usize = 20 * 1024 * 1024;
+/// Maximum length of an upload key, the leases use 32 characters
+const MAX_UPLOAD_KEY_SIZE: usize = 64;
+/// Number of modlog pages searched for bans, Lemmy has no list of the users banned from a community
+const MAX_BAN_PAGES: i64 = 10;
+/// Number of modlog pages merged for a page of a community's moderation log
+const MAX_MODLOG_PAGES: i64 = 10;
+/// Number of report pages searched for the open reports of a post or comment
+const MAX_REPORT_PAGES: i64 = 10;
+
+/// The latest removal or approval of a post or comment, found in the modlog
+pub struct Removal {
+    pub removed: bool,
+    /// Escaped name of the moderator, if the instance doesn't hide them
+    pub moderator: Option<String>,
+    pub reason: Option<String>,
+    pub when: f64,
+}
+
 pub struct ResponseState<'a> {
     pub app: &'a AppState,
     pub config: &'a server_config::GatewayConfig,
     pub res_config: ResponseConfig,
+    /// Lemmy JWT of the logged in user, taken from the bearer token or the session cookie
+    pub auth: Option<Sensitive<String>>,
+    /// Modhash of the cookie session, `None` for clients using bearer tokens
+    pub modhash: Option<String>,
+    /// Did a cookie session send its modhash, if this request changes anything?
+    pub modhash_valid: bool,
+    /// Proxy paths for links to posts and comments, filled by [`ResponseState::resolve_links`]
+    pub links: HashMap<String, String>,
+    /// Known moderators of the communities in this response
+    pub moderators: HashSet<(CommunityId, PersonId)>,
+    /// The site, filled by [`ResponseState::load_site`] so that it is fetched at most once per request.
+    /// Its `my_user` is moved to [`ResponseState::my_user`].
+    pub site: Option<GetSiteResponse>,
+    /// The logged in user, filled by [`ResponseState::load_my_user`]
+    pub my_user: Option<MyUserInfo>,
+    /// Removals and approvals by fullname, filled by [`ResponseState::load_removals`]
+    pub removals: HashMap<Fullname, Removal>,
+    /// Communities the logged in user is banned from, filled by [`ResponseState::load_banned_from`]
+    pub banned_from: HashSet<CommunityId>,
 }
 
 pub fn prepare(req: &HttpRequest) -> Result<ResponseState, server_config::ServerSideError> {
@@ -82,6 +325,34 @@ pub fn prepare(req: &HttpRequest) -> Result<ResponseState, server_config::Server
 
     let _user_agent = req.headers().get("user-agent");
 
+    let bearer = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.split_once(' '))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+        .map(|(_, token)| Sensitive::new(token.to_owned()));
+
+    let session = match bearer {
+        Some(_) => None,
+        None => req.cookie(SESSION_COOKIE).and_then(|cookie| {
+            let mut sessions = app.sessions.lock().expect("lock not poisoned");
+            let now = Instant::now();
+            sessions.retain(|_, session| session.expires > now);
+            sessions.get(cookie.value()).cloned()
+        }),
+    };
+    let modhash_valid = match &session {
+        Some(session) if !matches!(*req.method(), Method::GET | Method::HEAD) => {
+            sent_modhash(req).as_deref() == Some(session.modhash.as_str())
+        }
+        _ => true,
+    };
+    let (auth, modhash) = match session {
+        Some(session) => (Some(session.jwt), Some(session.modhash)),
+        None => (bearer, None),
+    };
+
     let res_config = ResponseConfig {
         raw_json: req.query_string().contains("raw_json=1"),
         escape_names: true,
@@ -92,11 +363,127 @@ pub fn prepare(req: &HttpRequest) -> Result<ResponseState, server_config::Server
         app,
         config,
         res_config,
+        auth,
+        modhash,
+        modhash_valid,
+        links: HashMap::new(),
+        moderators: HashSet::new(),
+        site: None,
+        my_user: None,
+        removals: HashMap::new(),
+        banned_from: HashSet::new(),
     };
     // TODO: use headers to determine how much deviation from standard API this client can handle
     Ok(state)
 }
 
+#[derive(Deserialize)]
+struct ModhashQuery {
+    uh: Option<String>,
+}
+
+/// The modhash sent as `uh` in a form body, kept in the request by [`ModhashForm`]
+struct FormModhash(String);
+
+/// The modhash sent with a request, as `X-Modhash` header, `uh` query parameter
+/// or `uh` in a form body read by [`ModhashForm`]
+fn sent_modhash(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get("x-modhash")
+        .and_then(|h| h.to_str().ok())
+        .map(ToOwned::to_owned)
+        .or_else(|| {
+            web::Query::<ModhashQuery>::from_query(req.query_string())
+                .ok()
+                .and_then(|q| q.into_inner().uh)
+        })
+        .or_else(|| req.extensions().get::<FormModhash>().map(|uh| uh.0.clone()))
+}
+
+/// A urlencoded form like `web::Form`, which also takes the modhash reddit clients send as `uh` in it.
+/// Has to be extracted before [`prepare`] checks the modhash.
+pub struct ModhashForm<T>(pub T);
+
+impl<T> ModhashForm<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::ops::Deref for ModhashForm<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned + 'static> FromRequest for ModhashForm<T> {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        let body = web::Bytes::from_request(&req, payload);
+        Box::pin(async move {
+            let body = body.await?;
+            let body = std::str::from_utf8(&body).map_err(actix_web::error::ErrorBadRequest)?;
+            if let Some(uh) = web::Query::<ModhashQuery>::from_query(body)
+                .ok()
+                .and_then(|q| q.into_inner().uh)
+            {
+                req.extensions_mut().insert(FormModhash(uh));
+            }
+            Ok(ModhashForm(web::Query::<T>::from_query(body)?.into_inner()))
+        })
+    }
+}
+
+/// Create a cookie session for a Lemmy JWT and respond with it like reddit's `/api/login`.
+/// The cookie is marked secure if the login came in over HTTPS.
+fn start_session(
+    req: &HttpRequest,
+    state: &ResponseState,
+    jwt: Sensitive<String>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut rng = rand::thread_rng();
+    let cookie = Alphanumeric.sample_string(&mut rng, 40);
+    let modhash = Alphanumeric.sample_string(&mut rng, 50).to_lowercase();
+    {
+        let mut sessions = state.app.sessions.lock().expect("lock not poisoned");
+        let now = Instant::now();
+        sessions.retain(|_, session| session.expires > now);
+        sessions.insert(
+            cookie.clone(),
+            LoginSession {
+                jwt,
+                modhash: modhash.clone(),
+                expires: now + SESSION_DURATION,
+            },
+        );
+    }
+    let secure = req.connection_info().scheme() == "https";
+
+    let body = serde_json::to_string(&JsonResponse::data(api::Session {
+        need_https: false,
+        modhash,
+        cookie: cookie.clone(),
+    }))?;
+    Ok(HttpResponse::Ok()
+        .insert_header(ContentType::json())
+        .cookie(
+            Cookie::build(SESSION_COOKIE, cookie)
+                .path("/")
+                .http_only(true)
+                .secure(secure)
+                .max_age(actix_web::cookie::time::Duration::seconds(
+                    SESSION_DURATION.as_secs() as i64,
+                ))
+                .finish(),
+        )
+        .body(body))
+}
+
 impl<'a> ResponseState<'a> {
     pub fn escape_actor_id_str(&self, actor_id: &str) -> Option<String> {
         if let [instance, _ty, name] = actor_id
@@ -129,21 +516,77 @@ impl<'a> ResponseState<'a> {
         }
     }
 
+    /// The Lemmy JWT of the logged in user, for API calls that require one.
+    /// Fails for cookie sessions that didn't send their modhash with a request changing something.
+    pub fn auth(&self) -> Result<Sensitive<String>, server_config::ServerSideError> {
+        if !self.modhash_valid {
+            return Err(server_config::ServerSideError::InvalidModhash);
+        }
+        self.auth
+            .clone()
+            .ok_or(server_config::ServerSideError::NotLoggedIn)
+    }
+
+    /// Send a request to the Lemmy API, turning Lemmy's `{"error": ...}` responses into errors
+    async fn send(&self, req: RequestBuilder) -> Result<String, server_config::ServerSideError> {
+        #[derive(Deserialize)]
+        struct LemmyError {
+            error: String,
+        }
+
+        let res = req.send().await?;
+        let status = res.status();
+        let text = res.text().await?;
+        if !status.is_success() {
+            let error = serde_json::from_str::<LemmyError>(&text)
+                .map_or_else(|_| status.to_string(), |e| e.error);
+            return Err(server_config::ServerSideError::LemmyError(error));
+        }
+        Ok(text)
+    }
+
     pub async fn api_call(
         &self,
         endpoint: &str,
         params: &impl Serialize,
     ) -> Result<String, server_config::ServerSideError> {
         let api_url = &self.config.lemmy_url;
-        Ok(self
+        let req = self
             .app
             .http_client
             .get(format!("{api_url}/{endpoint}"))
-            .query(params)
-            .send()
-            .await?
-            .text()
-            .await?)
+            .query(params);
+        self.send(req).await
+    }
+
+    /// POST `params` as JSON to a Lemmy API endpoint
+    pub async fn api_post_typed<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        params: &impl Serialize,
+    ) -> Result<T, server_config::ServerSideError> {
+        let api_url = &self.config.lemmy_url;
+        let req = self
+            .app
+            .http_client
+            .post(format!("{api_url}/{endpoint}"))
+            .json(params);
+        Ok(serde_json::from_str(&self.send(req).await?)?)
+    }
+
+    /// PUT `params` as JSON to a Lemmy API endpoint
+    pub async fn api_put_typed<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        params: &impl Serialize,
+    ) -> Result<T, server_config::ServerSideError> {
+        let api_url = &self.config.lemmy_url;
+        let req = self
+            .app
+            .http_client
+            .put(format!("{api_url}/{endpoint}"))
+            .json(params);
+        Ok(serde_json::from_str(&self.send(req).await?)?)
     }
 
     pub async fn api_call_typed<T: DeserializeOwned>(
@@ -176,6 +619,20 @@ impl<'a> ResponseState<'a> {
         self.api_call_typed("api/v3/comment/list", params).await
     }
 
+    pub async fn get_comment(
+        &self,
+        params: &GetComment,
+    ) -> Result<CommentResponse, server_config::ServerSideError> {
+        self.api_call_typed("api/v3/comment", params).await
+    }
+
+    pub async fn search(
+        &self,
+        params: &Search,
+    ) -> Result<SearchResponse, server_config::ServerSideError> {
+        self.api_call_typed("api/v3/search", params).await
+    }
+
     pub async fn get_community(
         &self,
         params: &GetCommunity,
@@ -189,6 +646,462 @@ impl<'a> ResponseState<'a> {
     ) -> Result<GetPersonDetailsResponse, server_config::ServerSideError> {
         self.api_call_typed("api/v3/user", params).await
     }
+
+    pub async fn get_site(
+        &self,
+        params: &GetSite,
+    ) -> Result<GetSiteResponse, server_config::ServerSideError> {
+        self.api_call_typed("api/v3/site", params).await
+    }
+
+    pub async fn distinguish_comment(
+        &self,
+        params: &DistinguishComment,
+    ) -> Result<CommentResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/comment/distinguish", params)
+            .await
+    }
+
+    pub async fn remove_post(
+        &self,
+        params: &RemovePost,
+    ) -> Result<PostResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/post/remove", params).await
+    }
+
+    pub async fn remove_comment(
+        &self,
+        params: &RemoveComment,
+    ) -> Result<CommentResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/comment/remove", params).await
+    }
+
+    pub async fn lock_post(
+        &self,
+        params: &LockPost,
+    ) -> Result<PostResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/post/lock", params).await
+    }
+
+    pub async fn feature_post(
+        &self,
+        params: &FeaturePost,
+    ) -> Result<PostResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/post/feature", params).await
+    }
+
+    pub async fn edit_post(
+        &self,
+        params: &EditPost,
+    ) -> Result<PostResponse, server_config::ServerSideError> {
+        self.api_put_typed("api/v3/post", params).await
+    }
+
+    pub async fn get_modlog(
+        &self,
+        params: &GetModlog,
+    ) -> Result<GetModlogResponse, server_config::ServerSideError> {
+        self.api_call_typed("api/v3/modlog", params).await
+    }
+
+    pub async fn create_post_report(
+        &self,
+        params: &CreatePostReport,
+    ) -> Result<PostReportResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/post/report", params).await
+    }
+
+    pub async fn create_comment_report(
+        &self,
+        params: &CreateCommentReport,
+    ) -> Result<CommentReportResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/comment/report", params).await
+    }
+
+    pub async fn list_post_reports(
+        &self,
+        params: &ListPostReports,
+    ) -> Result<ListPostReportsResponse, server_config::ServerSideError> {
+        self.api_call_typed("api/v3/post/report/list", params).await
+    }
+
+    pub async fn list_comment_reports(
+        &self,
+        params: &ListCommentReports,
+    ) -> Result<ListCommentReportsResponse, server_config::ServerSideError> {
+        self.api_call_typed("api/v3/comment/report/list", params)
+            .await
+    }
+
+    pub async fn resolve_post_report(
+        &self,
+        params: &ResolvePostReport,
+    ) -> Result<PostReportResponse, server_config::ServerSideError> {
+        self.api_put_typed("api/v3/post/report/resolve", params)
+            .await
+    }
+
+    pub async fn resolve_comment_report(
+        &self,
+        params: &ResolveCommentReport,
+    ) -> Result<CommentReportResponse, server_config::ServerSideError> {
+        self.api_put_typed("api/v3/comment/report/resolve", params)
+            .await
+    }
+
+    pub async fn ban_from_community(
+        &self,
+        params: &BanFromCommunity,
+    ) -> Result<BanFromCommunityResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/community/ban_user", params)
+            .await
+    }
+
+    pub async fn block_person(
+        &self,
+        params: &BlockPerson,
+    ) -> Result<BlockPersonResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/user/block", params).await
+    }
+
+    pub async fn block_community(
+        &self,
+        params: &BlockCommunity,
+    ) -> Result<BlockCommunityResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/community/block", params).await
+    }
+
+    pub async fn save_user_settings(
+        &self,
+        params: &SaveUserSettings,
+    ) -> Result<LoginResponse, server_config::ServerSideError> {
+        self.api_put_typed("api/v3/user/save_user_settings", params)
+            .await
+    }
+
+    pub async fn register(
+        &self,
+        params: &Register,
+    ) -> Result<LoginResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/user/register", params).await
+    }
+
+    pub async fn login(
+        &self,
+        params: &Login,
+    ) -> Result<LoginResponse, server_config::ServerSideError> {
+        self.api_post_typed("api/v3/user/login", params).await
+    }
+
+    pub async fn get_captcha(&self) -> Result<GetCaptchaResponse, server_config::ServerSideError> {
+        self.api_call_typed("api/v3/user/get_captcha", &GetCaptcha {})
+            .await
+    }
+
+    /// The latest bans of the persons currently banned from a community, or only of `person_id`, from its modlog
+    pub async fn community_bans(
+        &self,
+        community_id: CommunityId,
+        person_id: Option<PersonId>,
+    ) -> Result<Vec<ModBanFromCommunityView>, server_config::ServerSideError> {
+        let mut bans: Vec<ModBanFromCommunityView> = Vec::new();
+        let mut seen = HashSet::new();
+        for page in 1..=MAX_BAN_PAGES {
+            let res = self
+                .get_modlog(&GetModlog {
+                    community_id: Some(community_id),
+                    other_person_id: person_id,
+                    type_: Some(ModlogActionType::ModBanFromCommunity),
+                    page: Some(page),
+                    limit: Some(MODLOG_LIMIT),
+                    auth: self.auth.clone(),
+                    ..Default::default()
+                })
+                .await?;
+            let len = res.banned_from_community.len();
+            // the modlog is sorted newest first, only the latest ban or unban of a person counts
+            for ban in res.banned_from_community {
+                if seen.insert(ban.banned_person.id) {
+                    bans.push(ban);
+                }
+            }
+            if (len as i64) < MODLOG_LIMIT {
+                break;
+            }
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        bans.retain(|b| {
+            let ban = &b.mod_ban_from_community;
+            ban.banned.unwrap_or(true) && ban.expires.filter(|&e| e <= now).is_none()
+        });
+        Ok(bans)
+    }
+
+    /// Check whether the logged in user is banned from a community. Requires [`ResponseState::load_my_user`].
+    pub async fn load_banned_from(
+        &mut self,
+        community_id: CommunityId,
+    ) -> Result<(), server_config::ServerSideError> {
+        let Some(my_user) = &self.my_user else {
+            return Ok(());
+        };
+        let person_id = my_user.local_user_view.person.id;
+        if !self
+            .community_bans(community_id, Some(person_id))
+            .await?
+            .is_empty()
+        {
+            self.banned_from.insert(community_id);
+        }
+        Ok(())
+    }
+
+    pub fn add_moderators(&mut self, moderators: &[CommunityModeratorView]) {
+        self.moderators
+            .extend(moderators.iter().map(|m| (m.community.id, m.moderator.id)));
+    }
+
+    /// Load the site, including the logged in user if there is one
+    pub async fn load_site(&mut self) -> Result<&GetSiteResponse, server_config::ServerSideError> {
+        if self.site.is_none() {
+            let mut site = self
+                .get_site(&GetSite {
+                    auth: self.auth.clone(),
+                })
+                .await?;
+            self.my_user = site.my_user.take();
+            self.site = Some(site);
+        }
+        Ok(self.site.as_ref().expect("site is loaded"))
+    }
+
+    /// Load the logged in user's info, including the communities they moderate, if there is one
+    pub async fn load_my_user(&mut self) -> Result<(), server_config::ServerSideError> {
+        if self.auth.is_some() {
+            self.load_site().await?;
+        }
+        Ok(())
+    }
+
+    /// Has the logged in user blocked the community?
+    pub fn community_blocked(&self, community_id: CommunityId) -> bool {
+        self.my_user.as_ref().is_some_and(|u| {
+            u.community_blocks
+                .iter()
+                .any(|b| b.community.id == community_id)
+        })
+    }
+
+    /// Posts from all communities except the ones the logged in user has blocked.
+    /// Lemmy doesn't always leave them out, so further pages fill up the page they were removed from.
+    /// Requires [`ResponseState::load_my_user`].
+    pub async fn get_unblocked_posts(
+        &self,
+        params: GetPosts,
+    ) -> Result<Vec<PostView>, server_config::ServerSideError> {
+        let limit = params.limit.unwrap_or(POSTS_LIMIT);
+        let first_page = params.page.unwrap_or(1);
+        let mut posts = Vec::new();
+        for page in first_page..first_page + MAX_FILTERED_PAGES {
+            let res = self
+                .get_posts(&GetPosts {
+                    page: Some(page),
+                    limit: Some(limit),
+                    ..params.clone()
+                })
+                .await?;
+            let count = res.posts.len();
+            posts.extend(
+                res.posts
+                    .into_iter()
+                    .filter(|pv| !self.community_blocked(pv.community.id)),
+            );
+            if count < limit as usize || posts.len() >= limit as usize {
+                break;
+            }
+        }
+        posts.truncate(limit as usize);
+        Ok(posts)
+    }
+
+    /// Can the logged in user moderate the community?
+    pub fn can_mod(&self, community_id: CommunityId) -> bool {
+        self.my_user.as_ref().is_some_and(|u| {
+            u.local_user_view.person.admin
+                || u.moderates.iter().any(|m| m.community.id == community_id)
+        })
+    }
+
+    /// Look up the latest removals and approvals in the modlogs of the communities the logged in user moderates,
+    /// so that moderators can see who removed or approved content and why.
+    /// Requires [`ResponseState::load_my_user`] and ignores modlogs that can't be loaded.
+    pub async fn load_removals(&mut self, community_ids: impl IntoIterator<Item = CommunityId>) {
+        let mut community_ids: Vec<CommunityId> = community_ids
+            .into_iter()
+            .filter(|&id| self.can_mod(id))
+            .collect();
+        community_ids.sort_by_key(|id| id.0);
+        community_ids.dedup();
+
+        let params: Vec<GetModlog> = community_ids
+            .into_iter()
+            .map(|community_id| GetModlog {
+                community_id: Some(community_id),
+                limit: Some(MODLOG_LIMIT),
+                auth: self.auth.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let modlogs = futures::future::join_all(params.iter().map(|p| self.get_modlog(p))).await;
+
+        let mut found = Vec::new();
+        for modlog in modlogs.into_iter().flatten() {
+            for rp in modlog.removed_posts {
+                let action = rp.mod_remove_post;
+                let removal = Removal {
+                    removed: action.removed.unwrap_or(true),
+                    moderator: rp.moderator.and_then(|m| self.escape_actor_id(&m.actor_id)),
+                    reason: action.reason,
+                    when: api_translation::timestamp(action.when_),
+                };
+                found.push((
+                    api_translation::fullname(ThingKind::Link, action.post_id.0),
+                    removal,
+                ));
+            }
+            for rc in modlog.removed_comments {
+                let action = rc.mod_remove_comment;
+                let removal = Removal {
+                    removed: action.removed.unwrap_or(true),
+                    moderator: rc.moderator.and_then(|m| self.escape_actor_id(&m.actor_id)),
+                    reason: action.reason,
+                    when: api_translation::timestamp(action.when_),
+                };
+                found.push((
+                    api_translation::fullname(ThingKind::Comment, action.comment_id.0),
+                    removal,
+                ));
+            }
+        }
+        // the modlog is sorted newest first, only keep the latest action for each thing
+        for (fullname, removal) in found {
+            self.removals.entry(fullname).or_insert(removal);
+        }
+    }
+
+    pub async fn resolve_object(
+        &self,
+        params: &ResolveObject,
+    ) -> Result<ResolveObjectResponse, server_config::ServerSideError> {
+        self.api_call_typed("api/v3/resolve_object", params).await
+    }
+
+    /// Hosts of Lemmy itself and of the instances it federates with
+    pub async fn known_instances(&mut self) -> HashSet<String> {
+        if let Some(known) = self
+            .app
+            .known_instances
+            .lock()
+            .expect("lock not poisoned")
+            .as_ref()
+            .filter(|known| known.expires > Instant::now())
+        {
+            return known.hosts.clone();
+        }
+
+        let mut hosts = HashSet::from([api_translation::host(&self.config.lemmy_url).to_owned()]);
+        // without the list only links to Lemmy itself are resolved, try again next time
+        if let Ok(site) = self.load_site().await {
+            if let Some(instances) = &site.federated_instances {
+                hosts.extend(instances.linked.iter().cloned());
+            }
+            *self.app.known_instances.lock().expect("lock not poisoned") = Some(KnownInstances {
+                hosts: hosts.clone(),
+                expires: Instant::now() + KNOWN_INSTANCES_DURATION,
+            });
+        }
+        hosts
+    }
+
+    /// Resolve links to posts, comments and actors in `texts` to proxy paths, so that they can be rewritten
+    /// by [`api_translation::rewrite_content`]. Links that can't be resolved are left alone.
+    pub async fn resolve_links<'t>(&mut self, texts: impl IntoIterator<Item = &'t str>) {
+        let mut urls: Vec<String> = texts
+            .into_iter()
+            .flat_map(|text| api_translation::links_to_resolve(self, text))
+            .filter(|url| !self.links.contains_key(url))
+            .collect();
+        {
+            let resolved = self.app.resolved_links.lock().expect("lock not poisoned");
+            let now = Instant::now();
+            urls.retain(
+                |url| match resolved.get(url).filter(|link| link.expires > now) {
+                    Some(link) => {
+                        self.links.insert(url.clone(), link.path.clone());
+                        false
+                    }
+                    None => true,
+                },
+            );
+        }
+        if urls.is_empty() {
+            return;
+        }
+        // links to other sites can look like links to Lemmy objects, e.g. youtube.com/c/channel
+        let known = self.known_instances().await;
+        urls.retain(|url| known.contains(api_translation::host(url)));
+        urls.sort();
+        urls.dedup();
+        urls.truncate(MAX_RESOLVED_LINKS);
+
+        let params: Vec<ResolveObject> = urls
+            .iter()
+            .map(|url| ResolveObject {
+                q: url.clone(),
+                auth: self.auth.clone(),
+            })
+            .collect();
+        let resolved =
+            futures::future::join_all(params.iter().map(|p| self.resolve_object(p))).await;
+
+        for (url, res) in urls.into_iter().zip(resolved) {
+            let path = match res {
+                Ok(ResolveObjectResponse { post: Some(pv), .. }) => {
+                    Some(api_translation::post(self, pv).data.permalink)
+                }
+                Ok(ResolveObjectResponse {
+                    comment: Some(cv), ..
+                }) => api_translation::comment(self, cv).data.permalink,
+                Ok(ResolveObjectResponse {
+                    community: Some(cv),
+                    ..
+                }) => self
+                    .escape_actor_id(&cv.community.actor_id)
+                    .map(|name| format!("/r/{name}")),
+                Ok(ResolveObjectResponse {
+                    person: Some(pv), ..
+                }) => self
+                    .escape_actor_id(&pv.person.actor_id)
+                    .map(|name| format!("/u/{name}")),
+                _ => None,
+            };
+            if let Some(path) = path {
+                self.links.insert(url, path);
+            }
+        }
+
+        // links that couldn't be resolved are tried again next time
+        let mut resolved = self.app.resolved_links.lock().expect("lock not poisoned");
+        let now = Instant::now();
+        resolved.retain(|_, link| link.expires > now);
+        for (url, path) in &self.links {
+            resolved.entry(url.clone()).or_insert_with(|| ResolvedLink {
+                path: path.clone(),
+                expires: now + RESOLVED_LINKS_DURATION,
+            });
+        }
+    }
 }
 
 pub fn respond_json<T: Serialize>(
@@ -210,52 +1123,122 @@ async fn community_about(
     req: HttpRequest,
     path: web::Path<(String,)>,
 ) -> Result<HttpResponse, server_config::ServerSideError> {
-    let state = prepare(&req)?;
+    let mut state = prepare(&req)?;
     let (sr,) = path.into_inner();
 
     let sr = state.unescape_name(&sr).unwrap_or(sr);
 
     let params = GetCommunity {
         name: Some(sr),
-        auth: None,
+        auth: state.auth.clone(),
         ..Default::default()
     };
 
     let res = state.get_community(&params).await?;
+    state.load_my_user().await?;
+    state
+        .load_banned_from(res.community_view.community.id)
+        .await?;
+    state
+        .resolve_links(res.community_view.community.description.as_deref())
+        .await;
     let com = api_translation::community(&state, res.community_view);
     respond_json(&com)
 }
 
-#[derive(Debug, Deserialize)]
-struct CommunityPath {
-    subreddit: String,
-    sorting: Option<subreddit::SortOrder>,
-}
-
-async fn community(
+#[get("/r/{subreddit}/about/rules{_:/?}.json")]
+async fn community_rules(
     req: HttpRequest,
-    path: web::Path<CommunityPath>,
-    query: web::Query<subreddit::Query>,
+    path: web::Path<(String,)>,
 ) -> Result<HttpResponse, server_config::ServerSideError> {
-    let state = prepare(&req)?;
-    let path = path.into_inner();
+    let mut state = prepare(&req)?;
+    let (sr,) = path.into_inner();
 
-    let sort = path
-        .sorting
-        .and_then(|s| api_translation::submission_sort(s, query.0.time));
+    let sr = state.unescape_name(&sr).unwrap_or(sr);
 
-    let subreddit = path.subreddit;
+    let params = GetCommunity {
+        name: Some(sr),
+        auth: state.auth.clone(),
+        ..Default::default()
+    };
+
+    let res = state.get_community(&params).await?;
+    state
+        .resolve_links(res.community_view.community.description.as_deref())
+        .await;
+    let site_sidebar = state.load_site().await?.site_view.site.sidebar.clone();
+    let rules =
+        api_translation::community_rules(&state, res.community_view, site_sidebar.as_deref());
+    respond_json(&rules)
+}
+
+async fn community_wiki(
+    req: HttpRequest,
+    path: web::Path<(String,)>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    let (sr,) = path.into_inner();
+
+    let sr = state.unescape_name(&sr).unwrap_or(sr);
+
+    let params = GetCommunity {
+        name: Some(sr),
+        auth: state.auth.clone(),
+        ..Default::default()
+    };
+
+    let res = state.get_community(&params).await?;
+    state
+        .resolve_links(res.community_view.community.description.as_deref())
+        .await;
+    let wiki = api_translation::community_wiki(&state, res.community_view);
+    respond_json(&wiki)
+}
+
+#[derive(Debug, Deserialize)]
+struct CommunityPath {
+    subreddit: String,
+    sorting: Option<subreddit::SortOrder>,
+}
+
+async fn community(
+    req: HttpRequest,
+    path: web::Path<CommunityPath>,
+    query: web::Query<subreddit::Query>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    let path = path.into_inner();
+
+    let sort = path
+        .sorting
+        .and_then(|s| api_translation::submission_sort(s, query.0.time));
+
+    let subreddit = path.subreddit;
     let subreddit = state.unescape_name(&subreddit).unwrap_or(subreddit);
+    // r/all is every community the instance knows about, except the ones the user has blocked
+    let all = subreddit == "all";
 
     let params = GetPosts {
         sort,
-        community_name: Some(subreddit),
-        auth: None,
+        type_: all.then_some(ListingType::All),
+        community_name: (!all).then_some(subreddit),
+        auth: state.auth.clone(),
         ..Default::default()
     };
 
-    let res = state.get_posts(&params).await?;
-    let posts = api_translation::posts(&state, res.posts);
+    state.load_my_user().await?;
+    let posts = if all {
+        state.get_unblocked_posts(params).await?
+    } else {
+        state.get_posts(&params).await?.posts
+    };
+    state
+        .load_removals(posts.iter().map(|pv| pv.community.id))
+        .await;
+    state
+        .resolve_links(posts.iter().filter_map(|pv| pv.post.body.as_deref()))
+        .await;
+    let posts = api_translation::posts(&state, posts);
     respond_json(&posts)
 }
 
@@ -265,25 +1248,34 @@ async fn frontpage(
     path: web::Path<(subreddit::SortOrder,)>,
     query: web::Query<subreddit::Query>,
 ) -> Result<HttpResponse, server_config::ServerSideError> {
-    let state = prepare(&req)?;
+    let mut state = prepare(&req)?;
     let (sorting,) = path.into_inner();
     let sort = api_translation::submission_sort(sorting, query.0.time);
 
     let params = GetPosts {
         sort,
-        auth: None,
+        auth: state.auth.clone(),
         type_: Some(ListingType::All),
         ..Default::default()
     };
 
-    let res = state.get_posts(&params).await?;
-    let posts = api_translation::posts(&state, res.posts);
+    state.load_my_user().await?;
+    let posts = state.get_unblocked_posts(params).await?;
+    state
+        .load_removals(posts.iter().map(|pv| pv.community.id))
+        .await;
+    state
+        .resolve_links(posts.iter().filter_map(|pv| pv.post.body.as_deref()))
+        .await;
+    let posts = api_translation::posts(&state, posts);
     respond_json(&posts)
 }
 
 #[derive(Debug, Deserialize)]
 struct CommentsPath {
-    post_id: i32,
+    post_id: Id36,
+    /// if set, get replies subtree for this comment
+    comment_id: Option<Id36>,
 }
 
 async fn comments_for_post(
@@ -291,20 +1283,51 @@ async fn comments_for_post(
     path: web::Path<CommentsPath>,
     query: web::Query<submission::Query>,
 ) -> Result<HttpResponse, server_config::ServerSideError> {
-    let state = prepare(&req)?;
-    let post_id = path.into_inner().post_id;
-    let query = query.0;
+    let path = path.into_inner();
+    let post_id = lemmy_id(path.post_id.0)?;
+    let comment_id = match path.comment_id.or(query.comment) {
+        Some(comment_id) => Some(lemmy_id(comment_id.0)?),
+        None => None,
+    };
+    post_comments(req, post_id, comment_id, query.0).await
+}
+
+/// A post with its comments at Lemmy's own path, which has the decimal post ID.
+/// The IDs in `/comments/{id}` are always base 36, like on reddit.
+#[get("/post/{post_id}{_:/?}.json")]
+async fn lemmy_post(
+    req: HttpRequest,
+    path: web::Path<(i32,)>,
+    query: web::Query<submission::Query>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let (post_id,) = path.into_inner();
+    let comment_id = match query.comment {
+        Some(comment_id) => Some(lemmy_id(comment_id.0)?),
+        None => None,
+    };
+    post_comments(req, post_id, comment_id, query.0).await
+}
+
+/// A post and its comments, or only the replies to `comment_id`
+async fn post_comments(
+    req: HttpRequest,
+    post_id: i32,
+    comment_id: Option<i32>,
+    query: submission::Query,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
     let sort = query.sort.and_then(api_translation::comment_sort);
 
     let res = state
         .get_post(&GetPost {
             id: Some(PostId(post_id)),
-            auth: None,
+            auth: state.auth.clone(),
             comment_id: None,
         })
         .await?;
-    let post = api_translation::post(&state, res.post_view);
-    let post_listing = Listing::new(vec![post]);
+    let post_view = res.post_view;
+    let cross_posts = res.cross_posts;
+    state.add_moderators(&res.moderators);
     let res = state
         .get_comments(&GetComments {
             type_: Some(ListingType::All),
@@ -313,15 +1336,62 @@ async fn comments_for_post(
             page: None,
             limit: Some(query.limit.unwrap_or(100) as i64),
             post_id: Some(PostId(post_id)),
-            auth: None,
+            parent_id: comment_id.map(CommentId),
+            auth: state.auth.clone(),
             ..Default::default()
         })
         .await?;
+    state.load_my_user().await?;
+    state.load_removals([post_view.community.id]).await;
+    let texts = res.comments.iter().map(|cv| cv.comment.content.as_str());
+    state
+        .resolve_links(post_view.post.body.as_deref().into_iter().chain(texts))
+        .await;
+
+    let post = api_translation::post_with_crossposts(&state, post_view, cross_posts);
+    let post_listing = Listing::new(vec![post]);
     let comments = api_translation::comments(&state, res.comments);
 
     respond_json(&(post_listing, comments))
 }
 
+/// The post and the other posts linking to the same URL
+#[get("/duplicates/{post_id}{_:/?}.json")]
+async fn duplicates(
+    req: HttpRequest,
+    path: web::Path<(Id36,)>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    let (post_id,) = path.into_inner();
+    let post_id = lemmy_id(post_id.0)?;
+
+    let res = state
+        .get_post(&GetPost {
+            id: Some(PostId(post_id)),
+            auth: state.auth.clone(),
+            comment_id: None,
+        })
+        .await?;
+    state.add_moderators(&res.moderators);
+    state.load_my_user().await?;
+    let community_ids = res.cross_posts.iter().map(|pv| pv.community.id);
+    state
+        .load_removals(community_ids.chain([res.post_view.community.id]))
+        .await;
+    let texts = res
+        .cross_posts
+        .iter()
+        .filter_map(|pv| pv.post.body.as_deref());
+    state
+        .resolve_links(res.post_view.post.body.as_deref().into_iter().chain(texts))
+        .await;
+
+    let post =
+        api_translation::post_with_crossposts(&state, res.post_view, res.cross_posts.clone());
+    let duplicates = api_translation::posts(&state, res.cross_posts);
+    respond_json(&(Listing::new(vec![post]), duplicates))
+}
+
 #[get("/user/{username}/about{_:/?}.json")]
 async fn user_about(
     req: HttpRequest,
@@ -333,7 +1403,7 @@ async fn user_about(
     let res = state
         .get_user(&GetPersonDetails {
             username: Some(username),
-            auth: None,
+            auth: state.auth.clone(),
             ..Default::default()
         })
         .await?;
@@ -347,7 +1417,7 @@ async fn user_submitted(
     path: web::Path<(String,)>,
     query: web::Query<user::SubmissionQuery>,
 ) -> Result<HttpResponse, server_config::ServerSideError> {
-    let state = prepare(&req)?;
+    let mut state = prepare(&req)?;
     let username = path.into_inner().0;
     let username = state.unescape_name(&username).unwrap_or(username);
     let query = query.0;
@@ -360,11 +1430,18 @@ async fn user_submitted(
         .get_user(&GetPersonDetails {
             username: Some(username),
             sort,
-            auth: None,
+            auth: state.auth.clone(),
             ..Default::default()
         })
         .await?;
 
+    state.load_my_user().await?;
+    state
+        .load_removals(res.posts.iter().map(|pv| pv.community.id))
+        .await;
+    state
+        .resolve_links(res.posts.iter().filter_map(|pv| pv.post.body.as_deref()))
+        .await;
     let posts = api_translation::posts(&state, res.posts);
     respond_json(&posts)
 }
@@ -375,7 +1452,7 @@ async fn user_comments(
     path: web::Path<(String,)>,
     query: web::Query<user::SubmissionQuery>,
 ) -> Result<HttpResponse, server_config::ServerSideError> {
-    let state = prepare(&req)?;
+    let mut state = prepare(&req)?;
     let username = path.into_inner().0;
     let username = state.unescape_name(&username).unwrap_or(username);
     let query = query.0;
@@ -388,11 +1465,1459 @@ async fn user_comments(
         .get_user(&GetPersonDetails {
             username: Some(username),
             sort,
-            auth: None,
+            auth: state.auth.clone(),
             ..Default::default()
         })
         .await?;
 
+    state.load_my_user().await?;
+    state
+        .load_removals(res.comments.iter().map(|cv| cv.community.id))
+        .await;
+    state
+        .resolve_links(res.comments.iter().map(|cv| cv.comment.content.as_str()))
+        .await;
     let posts = api_translation::comments_flat(&state, res.comments);
     respond_json(&posts)
 }
+
+/// A post or comment that a moderation action applies to
+enum Thing {
+    Post(PostId),
+    Comment(CommentId),
+}
+
+/// The Lemmy ID of a thing, fails for IDs too large to be one
+fn lemmy_id(id: u64) -> Result<i32, server_config::ServerSideError> {
+    api_translation::lemmy_id(id)
+        .ok_or_else(|| server_config::ServerSideError::BadRequest(format!("invalid id {id}")))
+}
+
+fn parse_thing(fullname: Fullname) -> Result<Thing, server_config::ServerSideError> {
+    match fullname.kind {
+        ThingKind::Link => Ok(Thing::Post(PostId(lemmy_id(fullname.id)?))),
+        ThingKind::Comment => Ok(Thing::Comment(CommentId(lemmy_id(fullname.id)?))),
+        _ => Err(server_config::ServerSideError::BadRequest(format!(
+            "invalid id {fullname}"
+        ))),
+    }
+}
+
+/// Like [`parse_thing`], for actions Lemmy only supports on posts
+fn parse_post_id(
+    fullname: Fullname,
+    action: &str,
+) -> Result<PostId, server_config::ServerSideError> {
+    match parse_thing(fullname)? {
+        Thing::Post(post_id) => Ok(post_id),
+        Thing::Comment(_) => Err(server_config::ServerSideError::BadRequest(format!(
+            "Lemmy can't {action} comments"
+        ))),
+    }
+}
+
+/// Distinguish a comment as moderator or admin
+#[post("/api/distinguish")]
+async fn distinguish(
+    req: HttpRequest,
+    form: ModhashForm<api::Distinguish>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    let form = form.into_inner();
+
+    let comment_id = match parse_thing(form.id)? {
+        Thing::Comment(comment_id) => comment_id,
+        Thing::Post(_) => {
+            return Err(server_config::ServerSideError::BadRequest(
+                "Lemmy can't distinguish posts".to_owned(),
+            ))
+        }
+    };
+
+    let res = state
+        .distinguish_comment(&DistinguishComment {
+            comment_id,
+            distinguished: form.how != api::DistinguishHow::No,
+            auth: state.auth()?,
+        })
+        .await?;
+    state.load_my_user().await?;
+    state
+        .resolve_links([res.comment_view.comment.content.as_str()])
+        .await;
+
+    let comment = api_translation::comment(&state, res.comment_view);
+    respond_json(&JsonResponse::data(Things {
+        things: vec![RedditThing::Comment(comment.data)],
+    }))
+}
+
+async fn set_removed(
+    state: &ResponseState<'_>,
+    fullname: Fullname,
+    removed: bool,
+    reason: Option<String>,
+) -> Result<(), server_config::ServerSideError> {
+    match parse_thing(fullname)? {
+        Thing::Post(post_id) => {
+            state
+                .remove_post(&RemovePost {
+                    post_id,
+                    removed,
+                    reason,
+                    auth: state.auth()?,
+                })
+                .await?;
+        }
+        Thing::Comment(comment_id) => {
+            state
+                .remove_comment(&RemoveComment {
+                    comment_id,
+                    removed,
+                    reason,
+                    auth: state.auth()?,
+                })
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Approve a post or comment, restoring it if it was removed
+#[post("/api/approve")]
+async fn approve(
+    req: HttpRequest,
+    form: ModhashForm<api::ThingId>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    set_removed(&state, form.id, false, None).await?;
+    respond_json(&Empty {})
+}
+
+#[post("/api/remove")]
+async fn remove(
+    req: HttpRequest,
+    form: ModhashForm<api::Remove>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    // Lemmy has no spam filter to train, so spam is just a removal reason
+    let reason = form.spam.then(|| "spam".to_owned());
+    set_removed(&state, form.id, true, reason).await?;
+    respond_json(&Empty {})
+}
+
+async fn set_locked(
+    req: HttpRequest,
+    fullname: Fullname,
+    locked: bool,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let post_id = parse_post_id(fullname, "lock")?;
+    state
+        .lock_post(&LockPost {
+            post_id,
+            locked,
+            auth: state.auth()?,
+        })
+        .await?;
+    respond_json(&Empty {})
+}
+
+#[post("/api/lock")]
+async fn lock(
+    req: HttpRequest,
+    form: ModhashForm<api::ThingId>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    set_locked(req, form.id, true).await
+}
+
+#[post("/api/unlock")]
+async fn unlock(
+    req: HttpRequest,
+    form: ModhashForm<api::ThingId>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    set_locked(req, form.id, false).await
+}
+
+/// Sticky a post to its community, unstickying removes it from the community and the local front page
+#[post("/api/set_subreddit_sticky")]
+async fn set_subreddit_sticky(
+    req: HttpRequest,
+    form: ModhashForm<api::SetSubredditSticky>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let post_id = parse_post_id(form.id, "sticky")?;
+    if form.to_profile == Some(true) {
+        return Err(server_config::ServerSideError::BadRequest(
+            "Lemmy can't sticky posts to profiles".to_owned(),
+        ));
+    }
+
+    let feature_types = if form.state {
+        vec![PostFeatureType::Community]
+    } else {
+        let post = state
+            .get_post(&GetPost {
+                id: Some(post_id),
+                auth: state.auth.clone(),
+                comment_id: None,
+            })
+            .await?
+            .post_view
+            .post;
+        let mut feature_types = vec![];
+        if post.featured_community {
+            feature_types.push(PostFeatureType::Community);
+        }
+        if post.featured_local {
+            feature_types.push(PostFeatureType::Local);
+        }
+        feature_types
+    };
+
+    for feature_type in feature_types {
+        state
+            .feature_post(&FeaturePost {
+                post_id,
+                featured: form.state,
+                feature_type,
+                auth: state.auth()?,
+            })
+            .await?;
+    }
+    respond_json(&Empty {})
+}
+
+async fn set_nsfw(
+    req: HttpRequest,
+    fullname: Fullname,
+    nsfw: bool,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let post_id = parse_post_id(fullname, "mark NSFW")?;
+    state
+        .edit_post(&EditPost {
+            post_id,
+            name: None,
+            url: None,
+            body: None,
+            nsfw: Some(nsfw),
+            language_id: None,
+            auth: state.auth()?,
+        })
+        .await?;
+    respond_json(&Empty {})
+}
+
+#[post("/api/marknsfw")]
+async fn mark_nsfw(
+    req: HttpRequest,
+    form: ModhashForm<api::ThingId>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    set_nsfw(req, form.id, true).await
+}
+
+#[post("/api/unmarknsfw")]
+async fn unmark_nsfw(
+    req: HttpRequest,
+    form: ModhashForm<api::ThingId>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    set_nsfw(req, form.id, false).await
+}
+
+#[post("/api/report")]
+async fn report(
+    req: HttpRequest,
+    form: ModhashForm<api::Report>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let reason = form.reason();
+    match parse_thing(form.thing_id)? {
+        Thing::Post(post_id) => {
+            state
+                .create_post_report(&CreatePostReport {
+                    post_id,
+                    reason,
+                    auth: state.auth()?,
+                })
+                .await?;
+        }
+        Thing::Comment(comment_id) => {
+            state
+                .create_comment_report(&CreateCommentReport {
+                    comment_id,
+                    reason,
+                    auth: state.auth()?,
+                })
+                .await?;
+        }
+    }
+    respond_json(&JsonResponse::<Empty>::errors(vec![]))
+}
+
+/// Resolve all open reports of a post or comment
+#[post("/api/ignore_reports")]
+async fn ignore_reports(
+    req: HttpRequest,
+    form: ModhashForm<api::ThingId>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    // Collect the reports of all pages before resolving any, resolving them would shift the pages
+    match parse_thing(form.id)? {
+        Thing::Post(post_id) => {
+            let community_id = state
+                .get_post(&GetPost {
+                    id: Some(post_id),
+                    auth: state.auth.clone(),
+                    comment_id: None,
+                })
+                .await?
+                .post_view
+                .community
+                .id;
+            let mut report_ids = vec![];
+            for page in 1..=MAX_REPORT_PAGES {
+                let res = state
+                    .list_post_reports(&ListPostReports {
+                        community_id: Some(community_id),
+                        unresolved_only: Some(true),
+                        page: Some(page),
+                        limit: Some(REPORTS_LIMIT),
+                        auth: state.auth()?,
+                    })
+                    .await?;
+                let count = res.post_reports.len();
+                report_ids.extend(
+                    res.post_reports
+                        .iter()
+                        .filter(|r| r.post.id == post_id)
+                        .map(|r| r.post_report.id),
+                );
+                if count < REPORTS_LIMIT as usize {
+                    break;
+                }
+            }
+            for report_id in report_ids {
+                state
+                    .resolve_post_report(&ResolvePostReport {
+                        report_id,
+                        resolved: true,
+                        auth: state.auth()?,
+                    })
+                    .await?;
+            }
+        }
+        Thing::Comment(comment_id) => {
+            let community_id = state
+                .get_comment(&GetComment {
+                    id: comment_id,
+                    auth: state.auth.clone(),
+                })
+                .await?
+                .comment_view
+                .community
+                .id;
+            let mut report_ids = vec![];
+            for page in 1..=MAX_REPORT_PAGES {
+                let res = state
+                    .list_comment_reports(&ListCommentReports {
+                        community_id: Some(community_id),
+                        unresolved_only: Some(true),
+                        page: Some(page),
+                        limit: Some(REPORTS_LIMIT),
+                        auth: state.auth()?,
+                    })
+                    .await?;
+                let count = res.comment_reports.len();
+                report_ids.extend(
+                    res.comment_reports
+                        .iter()
+                        .filter(|r| r.comment.id == comment_id)
+                        .map(|r| r.comment_report.id),
+                );
+                if count < REPORTS_LIMIT as usize {
+                    break;
+                }
+            }
+            for report_id in report_ids {
+                state
+                    .resolve_comment_report(&ResolveCommentReport {
+                        report_id,
+                        resolved: true,
+                        auth: state.auth()?,
+                    })
+                    .await?;
+            }
+        }
+    }
+    respond_json(&Empty {})
+}
+
+/// Reported posts and comments. Lemmy doesn't filter anything for review, so the modqueue is the same.
+/// `/r/mod` lists the reports in all communities the user moderates.
+async fn mod_queue(
+    req: HttpRequest,
+    path: web::Path<(String,)>,
+    query: web::Query<subreddit::ModListingQuery>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    let (sr,) = path.into_inner();
+    let query = query.into_inner();
+    let limit = query
+        .limit
+        .map_or(REPORTS_LIMIT, |l| (l as i64).min(REPORTS_LIMIT));
+
+    let community_id = if sr == "mod" {
+        None
+    } else {
+        let sr = state.unescape_name(&sr).unwrap_or(sr);
+        let res = state
+            .get_community(&GetCommunity {
+                name: Some(sr),
+                auth: state.auth.clone(),
+                ..Default::default()
+            })
+            .await?;
+        state.add_moderators(&res.moderators);
+        Some(res.community_view.community.id)
+    };
+
+    let post_reports = if query.only != Some(subreddit::ModListingOnly::Comments) {
+        state
+            .list_post_reports(&ListPostReports {
+                community_id,
+                unresolved_only: Some(true),
+                limit: Some(limit),
+                auth: state.auth()?,
+                ..Default::default()
+            })
+            .await?
+            .post_reports
+    } else {
+        vec![]
+    };
+    let comment_reports = if query.only != Some(subreddit::ModListingOnly::Links) {
+        state
+            .list_comment_reports(&ListCommentReports {
+                community_id,
+                unresolved_only: Some(true),
+                limit: Some(limit),
+                auth: state.auth()?,
+                ..Default::default()
+            })
+            .await?
+            .comment_reports
+    } else {
+        vec![]
+    };
+
+    state.load_my_user().await?;
+    let community_ids = post_reports.iter().map(|r| r.community.id);
+    let community_ids = community_ids.chain(comment_reports.iter().map(|r| r.community.id));
+    state.load_removals(community_ids).await;
+    let texts = post_reports.iter().filter_map(|r| r.post.body.as_deref());
+    let texts = texts.chain(comment_reports.iter().map(|r| r.comment.content.as_str()));
+    state.resolve_links(texts).await;
+
+    let things = api_translation::reports(&state, post_reports, comment_reports);
+    respond_json(&things)
+}
+
+/// The public moderation log of a community. `after` and `before` are IDs of moderator actions.
+#[get("/r/{subreddit}/about/log{_:/?}.json")]
+async fn mod_log(
+    req: HttpRequest,
+    path: web::Path<(String,)>,
+    query: web::Query<modlog::Query>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let (sr,) = path.into_inner();
+    let query = query.into_inner();
+    let sr = state.unescape_name(&sr).unwrap_or(sr);
+
+    let type_ = match query.type_.as_deref() {
+        Some(action) => match api_translation::modlog_action_type(action) {
+            Some(type_) => Some(type_),
+            // Lemmy doesn't have this kind of action
+            None => return respond_json(&Listing::<ModAction>::new(vec![])),
+        },
+        None => None,
+    };
+
+    let mods: Option<Vec<String>> = query
+        .mod_
+        .map(|mods| mods.split(',').map(|m| m.trim().to_owned()).collect());
+    // Lemmy can only filter by a single moderator, multiple are filtered below
+    let mod_person_id = match mods.as_deref() {
+        Some([name]) => {
+            let username = state.unescape_name(name).unwrap_or(name.clone());
+            let res = state
+                .get_user(&GetPersonDetails {
+                    username: Some(username),
+                    auth: state.auth.clone(),
+                    ..Default::default()
+                })
+                .await?;
+            Some(res.person_view.person.id)
+        }
+        _ => None,
+    };
+
+    let community = state
+        .get_community(&GetCommunity {
+            name: Some(sr),
+            auth: state.auth.clone(),
+            ..Default::default()
+        })
+        .await?;
+
+    let limit = query
+        .limit
+        .map_or(MODLOG_LIMIT, |l| (l as i64).min(MODLOG_LIMIT)) as usize;
+
+    // Lemmy pages each type of action separately, merge pages until the requested page is known
+    let mut actions: Vec<ModActionData> = Vec::new();
+    let mut known = 0;
+    let mut complete = false;
+    for page in 1..=MAX_MODLOG_PAGES {
+        let res = state
+            .get_modlog(&GetModlog {
+                community_id: Some(community.community_view.community.id),
+                mod_person_id,
+                page: Some(page),
+                limit: Some(MODLOG_LIMIT),
+                type_,
+                auth: state.auth.clone(),
+                ..Default::default()
+            })
+            .await?;
+        let (page_actions, horizon) = api_translation::mod_actions(
+            &state,
+            res,
+            query.type_.as_deref(),
+            mods.as_deref(),
+            MODLOG_LIMIT,
+        );
+        actions.extend(page_actions);
+        actions.sort_by(|a, b| b.created_utc.total_cmp(&a.created_utc));
+
+        let Some(horizon) = horizon else {
+            known = actions.len();
+            complete = true;
+            break;
+        };
+        // older actions can still be missing from the types of actions that filled the page
+        known = actions
+            .iter()
+            .take_while(|a| a.created_utc >= horizon)
+            .count();
+        let position = |id: &str| actions[..known].iter().position(|a| a.id == id);
+        let needed = match (query.after.as_deref(), query.before.as_deref()) {
+            (Some(after), _) => position(after).map(|i| i + 1 + limit),
+            (None, Some(before)) => position(before),
+            (None, None) => Some(limit),
+        };
+        if needed.is_some_and(|needed| needed <= known) {
+            break;
+        }
+    }
+    actions.truncate(known);
+
+    let log = api_translation::mod_log(
+        &state,
+        actions,
+        complete,
+        query.after.as_deref(),
+        query.before.as_deref(),
+        limit,
+    );
+    respond_json(&log)
+}
+
+/// Users currently banned from a community
+#[get("/r/{subreddit}/about/banned{_:/?}.json")]
+async fn banned(
+    req: HttpRequest,
+    path: web::Path<(String,)>,
+    query: web::Query<user::UserListQuery>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let (sr,) = path.into_inner();
+    let sr = state.unescape_name(&sr).unwrap_or(sr);
+
+    let person_id = match &query.user {
+        Some(name) => Some(person_id(&state, name).await?),
+        None => None,
+    };
+    let community = state
+        .get_community(&GetCommunity {
+            name: Some(sr),
+            auth: state.auth.clone(),
+            ..Default::default()
+        })
+        .await?;
+
+    let bans = state
+        .community_bans(community.community_view.community.id, person_id)
+        .await?;
+    respond_json(&api_translation::banned_users(&state, bans))
+}
+
+#[derive(Debug, Deserialize)]
+struct SubredditPath {
+    subreddit: Option<String>,
+}
+
+/// The community a relationship applies to, from the URL or the fullname in `container`
+async fn container_community(
+    state: &ResponseState<'_>,
+    subreddit: Option<String>,
+    container: Option<Fullname>,
+) -> Result<CommunityId, server_config::ServerSideError> {
+    if let Some(container) = container.filter(|c| c.kind == ThingKind::Subreddit) {
+        return Ok(CommunityId(lemmy_id(container.id)?));
+    }
+    let Some(sr) = subreddit else {
+        return Err(server_config::ServerSideError::BadRequest(
+            "missing subreddit".to_owned(),
+        ));
+    };
+    let sr = state.unescape_name(&sr).unwrap_or(sr);
+    let res = state
+        .get_community(&GetCommunity {
+            name: Some(sr),
+            auth: state.auth.clone(),
+            ..Default::default()
+        })
+        .await?;
+    Ok(res.community_view.community.id)
+}
+
+async fn person_id(
+    state: &ResponseState<'_>,
+    name: &str,
+) -> Result<PersonId, server_config::ServerSideError> {
+    let username = state.unescape_name(name).unwrap_or(name.to_owned());
+    let res = state
+        .get_user(&GetPersonDetails {
+            username: Some(username),
+            auth: state.auth.clone(),
+            ..Default::default()
+        })
+        .await?;
+    Ok(res.person_view.person.id)
+}
+
+fn unsupported_relationship(
+    type_: api::RelationshipType,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    Err(server_config::ServerSideError::BadRequest(format!(
+        "Lemmy doesn't support {type_:?} relationships"
+    )))
+}
+
+async fn friend(
+    req: HttpRequest,
+    path: web::Path<SubredditPath>,
+    form: ModhashForm<api::Friend>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let form = form.into_inner();
+
+    match form.type_ {
+        api::RelationshipType::Banned => {
+            let community_id =
+                container_community(&state, path.into_inner().subreddit, form.container).await?;
+            let person_id = person_id(&state, &form.name).await?;
+            let expires = form
+                .duration
+                .map(|days| chrono::Utc::now().timestamp() + i64::from(days) * 24 * 60 * 60);
+            let reason = [form.ban_reason, form.ban_message]
+                .into_iter()
+                .flatten()
+                .find(|r| !r.trim().is_empty());
+            state
+                .ban_from_community(&BanFromCommunity {
+                    community_id,
+                    person_id,
+                    ban: true,
+                    remove_data: None,
+                    reason,
+                    expires,
+                    auth: state.auth()?,
+                })
+                .await?;
+        }
+        type_ => return unsupported_relationship(type_),
+    }
+    respond_json(&JsonResponse::<Empty>::errors(vec![]))
+}
+
+async fn unfriend(
+    req: HttpRequest,
+    path: web::Path<SubredditPath>,
+    form: ModhashForm<api::Unfriend>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let form = form.into_inner();
+
+    let account = form.id.filter(|id| id.kind == ThingKind::Account);
+    let person_id = match (&form.name, account) {
+        (_, Some(account)) => PersonId(lemmy_id(account.id)?),
+        (Some(name), _) => person_id(&state, name).await?,
+        _ => {
+            return Err(server_config::ServerSideError::BadRequest(
+                "missing user name".to_owned(),
+            ))
+        }
+    };
+
+    match form.type_ {
+        api::RelationshipType::Banned => {
+            let community_id =
+                container_community(&state, path.into_inner().subreddit, form.container).await?;
+            state
+                .ban_from_community(&BanFromCommunity {
+                    community_id,
+                    person_id,
+                    ban: false,
+                    remove_data: None,
+                    reason: None,
+                    expires: None,
+                    auth: state.auth()?,
+                })
+                .await?;
+        }
+        api::RelationshipType::Enemy => {
+            state
+                .block_person(&BlockPerson {
+                    person_id,
+                    block: false,
+                    auth: state.auth()?,
+                })
+                .await?;
+        }
+        type_ => return unsupported_relationship(type_),
+    }
+    respond_json(&JsonResponse::<Empty>::errors(vec![]))
+}
+
+#[post("/api/block_user")]
+async fn block_user(
+    req: HttpRequest,
+    form: ModhashForm<api::BlockUser>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let account = form.account_id.filter(|id| id.kind == ThingKind::Account);
+    let person_id = match (account, &form.name) {
+        (Some(account), _) => PersonId(lemmy_id(account.id)?),
+        (_, Some(name)) => person_id(&state, name).await?,
+        _ => {
+            return Err(server_config::ServerSideError::BadRequest(
+                "missing account_id or name".to_owned(),
+            ))
+        }
+    };
+
+    state
+        .block_person(&BlockPerson {
+            person_id,
+            block: true,
+            auth: state.auth()?,
+        })
+        .await?;
+    respond_json(&Empty {})
+}
+
+/// Users blocked by the logged in user
+async fn blocked(req: HttpRequest) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    state.load_my_user().await?;
+    let my_user = state
+        .my_user
+        .as_ref()
+        .ok_or(server_config::ServerSideError::NotLoggedIn)?;
+    respond_json(&api_translation::blocked_users(&state, my_user))
+}
+
+#[derive(Debug, Deserialize)]
+struct FilterPath {
+    username: String,
+    filter: String,
+    subreddit: Option<String>,
+}
+
+/// Only the logged in user's `all` filter exists, it is made of the communities they have blocked.
+/// Requires [`ResponseState::load_my_user`].
+fn check_filter(
+    state: &ResponseState,
+    path: &FilterPath,
+) -> Result<(), server_config::ServerSideError> {
+    let my_user = state
+        .my_user
+        .as_ref()
+        .ok_or(server_config::ServerSideError::NotLoggedIn)?;
+    let person = &my_user.local_user_view.person;
+    let own = path.username == person.name
+        || state.escape_actor_id(&person.actor_id).as_ref() == Some(&path.username);
+    if own && path.filter == "all" {
+        Ok(())
+    } else {
+        Err(server_config::ServerSideError::NotFound)
+    }
+}
+
+/// Communities the logged in user has blocked, which are left out of r/all and the front page
+async fn filtered_communities(
+    req: HttpRequest,
+    path: web::Path<FilterPath>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    state.load_my_user().await?;
+    check_filter(&state, &path)?;
+    let my_user = state
+        .my_user
+        .as_ref()
+        .ok_or(server_config::ServerSideError::NotLoggedIn)?;
+    respond_json(&api_translation::blocked_communities(&state, my_user))
+}
+
+async fn set_community_blocked(
+    req: HttpRequest,
+    path: FilterPath,
+    block: bool,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    state.load_my_user().await?;
+    check_filter(&state, &path)?;
+    let subreddit = path
+        .subreddit
+        .ok_or(server_config::ServerSideError::NotFound)?;
+    let community_id = container_community(&state, Some(subreddit.clone()), None).await?;
+    state
+        .block_community(&BlockCommunity {
+            community_id,
+            block,
+            auth: state.auth()?,
+        })
+        .await?;
+    respond_json(&api::MultiSubreddit { name: subreddit })
+}
+
+async fn filter_community(
+    req: HttpRequest,
+    path: web::Path<FilterPath>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    set_community_blocked(req, path.into_inner(), true).await
+}
+
+async fn unfilter_community(
+    req: HttpRequest,
+    path: web::Path<FilterPath>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    set_community_blocked(req, path.into_inner(), false).await
+}
+
+async fn prefs(req: HttpRequest) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    state.load_my_user().await?;
+    let my_user = state
+        .my_user
+        .as_ref()
+        .ok_or(server_config::ServerSideError::NotLoggedIn)?;
+    respond_json(&api_translation::prefs(my_user))
+}
+
+/// Update the preferences Lemmy supports and respond with all preferences,
+/// so that clients see that unsupported ones didn't change
+async fn update_prefs(
+    req: HttpRequest,
+    update: web::Json<api_translation::LemmyPrefs>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    let settings = api_translation::user_settings(update.into_inner(), state.auth()?)?;
+    state.save_user_settings(&settings).await?;
+
+    state.load_my_user().await?;
+    let my_user = state
+        .my_user
+        .as_ref()
+        .ok_or(server_config::ServerSideError::NotLoggedIn)?;
+    respond_json(&api_translation::prefs(my_user))
+}
+
+/// The logged in user's karma in each community they posted or commented in.
+/// Only the most recent content is counted for very active users.
+async fn karma(req: HttpRequest) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    state.load_my_user().await?;
+    let person_id = state
+        .my_user
+        .as_ref()
+        .ok_or(server_config::ServerSideError::NotLoggedIn)?
+        .local_user_view
+        .person
+        .id;
+
+    let mut posts = Vec::new();
+    let mut comments = Vec::new();
+    for page in 1..=MAX_KARMA_PAGES {
+        let res = state
+            .get_user(&GetPersonDetails {
+                person_id: Some(person_id),
+                sort: Some(SortType::New),
+                page: Some(page),
+                limit: Some(USER_CONTENT_LIMIT),
+                auth: state.auth.clone(),
+                ..Default::default()
+            })
+            .await?;
+        let done = (res.posts.len() as i64) < USER_CONTENT_LIMIT
+            && (res.comments.len() as i64) < USER_CONTENT_LIMIT;
+        posts.extend(res.posts);
+        comments.extend(res.comments);
+        if done {
+            break;
+        }
+    }
+
+    respond_json(&api_translation::karma(&state, &posts, &comments))
+}
+
+async fn trophies(
+    req: HttpRequest,
+    path: web::Path<(String,)>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let username = path.into_inner().0;
+    let username = state.unescape_name(&username).unwrap_or(username);
+    let res = state
+        .get_user(&GetPersonDetails {
+            username: Some(username),
+            auth: state.auth.clone(),
+            ..Default::default()
+        })
+        .await?;
+    respond_json(&api_translation::trophies(&res))
+}
+
+/// Hand out a lease to upload a file, uploads are received by [`media_upload`]
+#[post("/api/media/asset.json")]
+async fn media_asset(
+    req: HttpRequest,
+    form: ModhashForm<api::MediaAssetRequest>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let form = form.into_inner();
+    let key = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+
+    {
+        let mut leases = state.app.upload_leases.lock().expect("lock not poisoned");
+        let now = Instant::now();
+        leases.retain(|_, lease| lease.expires > now);
+        leases.insert(
+            key.clone(),
+            UploadLease {
+                auth: state.auth()?,
+                filepath: form.filepath.clone(),
+                mimetype: form.mimetype,
+                expires: now + UPLOAD_LEASE_DURATION,
+            },
+        );
+    }
+
+    let host = req.connection_info().host().to_owned();
+    respond_json(&api::MediaAsset {
+        args: api::UploadLease {
+            action: format!("//{host}/api/media/upload"),
+            fields: vec![api::UploadField {
+                name: "key".to_owned(),
+                value: key.clone(),
+            }],
+        },
+        asset: api::Asset {
+            asset_id: key,
+            processing_state: "incomplete".to_owned(),
+            payload: api::AssetPayload {
+                filepath: form.filepath,
+            },
+            websocket_url: None,
+        },
+    })
+}
+
+/// Receive a file uploaded with a lease from [`media_asset`] and forward it to pict-rs.
+/// Responds like the S3 bucket reddit uploads to, with the image URL in `Location`.
+#[post("/api/media/upload")]
+async fn media_upload(
+    req: HttpRequest,
+    mut payload: Multipart,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    #[derive(Deserialize)]
+    struct PictrsFile {
+        file: String,
+    }
+    #[derive(Deserialize)]
+    struct PictrsResponse {
+        files: Vec<PictrsFile>,
+    }
+
+    let state = prepare(&req)?;
+    let too_large = || server_config::ServerSideError::BadRequest("file too large".to_owned());
+    // Like S3, the key has to come before the file, so that nothing is buffered without a lease
+    let mut received = 0;
+    let mut lease = None;
+    let mut file = None;
+    while let Some(mut field) = payload.try_next().await? {
+        let name = field.name().to_owned();
+        match name.as_str() {
+            "key" => {
+                let mut key = Vec::new();
+                while let Some(chunk) = field.try_next().await? {
+                    received += chunk.len();
+                    if key.len() + chunk.len() > MAX_UPLOAD_KEY_SIZE {
+                        return Err(server_config::ServerSideError::BadRequest(
+                            "invalid upload key".to_owned(),
+                        ));
+                    }
+                    key.extend_from_slice(&chunk);
+                }
+                let key = String::from_utf8_lossy(&key).into_owned();
+                let found = state
+                    .app
+                    .upload_leases
+                    .lock()
+                    .expect("lock not poisoned")
+                    .remove(&key)
+                    .filter(|lease| lease.expires > Instant::now());
+                let Some(found) = found else {
+                    return Err(server_config::ServerSideError::BadRequest(
+                        "missing or expired upload key".to_owned(),
+                    ));
+                };
+                lease = Some((key, found));
+            }
+            "file" => {
+                if lease.is_none() {
+                    return Err(server_config::ServerSideError::BadRequest(
+                        "missing or expired upload key".to_owned(),
+                    ));
+                }
+                let mut data = Vec::new();
+                while let Some(chunk) = field.try_next().await? {
+                    received += chunk.len();
+                    if received > MAX_UPLOAD_SIZE {
+                        return Err(too_large());
+                    }
+                    data.extend_from_slice(&chunk);
+                }
+                file = Some(data);
+            }
+            // Policy fields of the S3 form, skipped without buffering
+            _ => {
+                while let Some(chunk) = field.try_next().await? {
+                    received += chunk.len();
+                    if received > MAX_UPLOAD_SIZE {
+                        return Err(too_large());
+                    }
+                }
+            }
+        }
+    }
+
+    let (Some((key, lease)), Some(file)) = (lease, file) else {
+        return Err(server_config::ServerSideError::BadRequest(
+            "missing or expired upload key or file".to_owned(),
+        ));
+    };
+
+    let part = multipart::Part::bytes(file)
+        .file_name(lease.filepath)
+        .mime_str(&lease.mimetype)?;
+    let lemmy_url = &state.config.lemmy_url;
+    let req = state
+        .app
+        .http_client
+        .post(format!("{lemmy_url}/pictrs/image"))
+        .header(header::COOKIE, format!("jwt={}", lease.auth.as_ref()))
+        .multipart(multipart::Form::new().part("images[]", part));
+    let res: PictrsResponse = serde_json::from_str(&state.send(req).await?)?;
+    let file = res.files.into_iter().next().ok_or_else(|| {
+        server_config::ServerSideError::LemmyError("pict-rs didn't store the file".to_owned())
+    })?;
+
+    let location = format!("{lemmy_url}/pictrs/image/{}", file.file);
+    let xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<PostResponse><Location>{}</Location><Bucket>pictrs</Bucket><Key>{}</Key></PostResponse>",
+        html_escape::encode_text(&location),
+        html_escape::encode_text(&key),
+    );
+    Ok(HttpResponse::Created()
+        .insert_header(ContentType::xml())
+        .body(xml))
+}
+
+/// Create an account, Lemmy's errors are returned in `json.errors`
+#[post("/api/register")]
+async fn register(
+    req: HttpRequest,
+    form: web::Form<api::Register>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let form = form.into_inner();
+    if let Some(iden) = &form.iden {
+        state
+            .app
+            .captchas
+            .lock()
+            .expect("lock not poisoned")
+            .remove(iden);
+    }
+
+    let password = Sensitive::new(form.passwd);
+    let params = Register {
+        username: form.user,
+        password_verify: form
+            .passwd2
+            .map_or_else(|| password.clone(), Sensitive::new),
+        password,
+        show_nsfw: false,
+        email: form
+            .email
+            .filter(|email| !email.is_empty())
+            .map(Sensitive::new),
+        captcha_uuid: form.iden,
+        captcha_answer: form.captcha,
+        honeypot: None,
+        answer: form.answer,
+    };
+    let res = match state.register(&params).await {
+        Ok(res) => res,
+        Err(server_config::ServerSideError::LemmyError(error)) => {
+            return respond_json(&JsonResponse::<api::Session>::errors(vec![
+                api_translation::api_error(&error),
+            ]));
+        }
+        Err(e) => return Err(e),
+    };
+
+    // no session until the application is approved or the email address is verified
+    let Some(jwt) = res.jwt else {
+        let error = if res.registration_created {
+            "registration_application_is_pending"
+        } else {
+            "email_not_verified"
+        };
+        return respond_json(&JsonResponse::<api::Session>::errors(vec![
+            api_translation::api_error(error),
+        ]));
+    };
+    start_session(&req, &state, jwt)
+}
+
+/// Log in with username and password, creating a cookie session
+async fn login(
+    req: HttpRequest,
+    form: web::Form<api::LoginForm>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let form = form.into_inner();
+    let res = match state
+        .login(&Login {
+            username_or_email: Sensitive::new(form.user),
+            password: Sensitive::new(form.passwd),
+        })
+        .await
+    {
+        Ok(res) => res,
+        Err(server_config::ServerSideError::LemmyError(error)) => {
+            return respond_json(&JsonResponse::<api::Session>::errors(vec![
+                api_translation::api_error(&error),
+            ]));
+        }
+        Err(e) => return Err(e),
+    };
+
+    match res.jwt {
+        Some(jwt) => start_session(&req, &state, jwt),
+        None => respond_json(&JsonResponse::<api::Session>::errors(vec![
+            api_translation::api_error("email_not_verified"),
+        ])),
+    }
+}
+
+/// The logged in user including the modhash of the session, `{}` if not logged in
+async fn me(req: HttpRequest) -> Result<HttpResponse, server_config::ServerSideError> {
+    let mut state = prepare(&req)?;
+    state.load_my_user().await?;
+    match &state.my_user {
+        Some(my_user) => respond_json(&api_translation::my_user(&state, my_user)),
+        None => respond_json(&Empty {}),
+    }
+}
+
+/// Get a new captcha from Lemmy, its image is served by [`captcha`]
+#[post("/api/new_captcha")]
+async fn new_captcha(req: HttpRequest) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let Some(captcha) = state.get_captcha().await?.ok else {
+        return respond_json(&JsonResponse::<api::Captcha>::errors(vec![api::ApiError(
+            "NO_CAPTCHA".to_owned(),
+            "this instance doesn't use captchas".to_owned(),
+            None,
+        )]));
+    };
+    let png = base64::engine::general_purpose::STANDARD
+        .decode(&captcha.png)
+        .map_err(|e| server_config::ServerSideError::LemmyError(e.to_string()))?;
+
+    {
+        let mut captchas = state.app.captchas.lock().expect("lock not poisoned");
+        let now = Instant::now();
+        captchas.retain(|_, captcha| captcha.expires > now);
+        captchas.insert(
+            captcha.uuid.clone(),
+            CaptchaImage {
+                png,
+                expires: now + CAPTCHA_DURATION,
+            },
+        );
+    }
+    respond_json(&JsonResponse::data(api::Captcha { iden: captcha.uuid }))
+}
+
+#[get("/captcha/{iden}.png")]
+async fn captcha(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    let Some(app) = req.app_data::<AppState>() else {
+        return HttpResponse::InternalServerError().finish();
+    };
+    let captchas = app.captchas.lock().expect("lock not poisoned");
+    match captchas
+        .get(path.as_str())
+        .filter(|captcha| captcha.expires > Instant::now())
+    {
+        Some(captcha) => HttpResponse::Ok()
+            .insert_header(ContentType::png())
+            .body(captcha.png.clone()),
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+/// A thing looked up by [`info`]
+enum Info {
+    /// with the moderators of its community, if known
+    Post(Box<PostView>, Vec<CommunityModeratorView>),
+    Comment(Box<CommentView>),
+    Community(Box<GetCommunityResponse>),
+}
+
+/// Is this Lemmy's error for a post, comment or community that doesn't exist?
+fn is_not_found(e: &server_config::ServerSideError) -> bool {
+    matches!(e, server_config::ServerSideError::LemmyError(e) if e.starts_with("couldnt_find_"))
+}
+
+/// Look up a post, comment or community by fullname, `None` if it doesn't exist
+async fn lookup_fullname(
+    state: &ResponseState<'_>,
+    fullname: Fullname,
+) -> Result<Option<Info>, server_config::ServerSideError> {
+    let Some(id) = api_translation::lemmy_id(fullname.id) else {
+        return Ok(None);
+    };
+    let res = match fullname.kind {
+        ThingKind::Link => state
+            .get_post(&GetPost {
+                id: Some(PostId(id)),
+                auth: state.auth.clone(),
+                comment_id: None,
+            })
+            .await
+            .map(|res| Info::Post(Box::new(res.post_view), res.moderators)),
+        ThingKind::Comment => state
+            .get_comment(&GetComment {
+                id: CommentId(id),
+                auth: state.auth.clone(),
+            })
+            .await
+            .map(|res| Info::Comment(Box::new(res.comment_view))),
+        ThingKind::Subreddit => state
+            .get_community(&GetCommunity {
+                id: Some(CommunityId(id)),
+                auth: state.auth.clone(),
+                ..Default::default()
+            })
+            .await
+            .map(|res| Info::Community(Box::new(res))),
+        _ => return Ok(None),
+    };
+    match res {
+        Ok(info) => Ok(Some(info)),
+        Err(e) if is_not_found(&e) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Look up things by fullname, a few at a time, leaving out the ones that don't exist
+async fn lookup_fullnames(
+    state: &ResponseState<'_>,
+    fullnames: Vec<Fullname>,
+) -> Result<Vec<Info>, server_config::ServerSideError> {
+    if fullnames.len() > MAX_INFO_IDS {
+        return Err(server_config::ServerSideError::BadRequest(format!(
+            "at most {MAX_INFO_IDS} things can be looked up at once"
+        )));
+    }
+    let things: Vec<Option<Info>> = futures::stream::iter(fullnames)
+        .map(|fullname| lookup_fullname(state, fullname))
+        .buffered(MAX_CONCURRENT_LOOKUPS)
+        .try_collect()
+        .await?;
+    Ok(things.into_iter().flatten().collect())
+}
+
+/// Look up a community by name, `None` if it doesn't exist
+async fn lookup_community(
+    state: &ResponseState<'_>,
+    name: &str,
+) -> Result<Option<Info>, server_config::ServerSideError> {
+    let name = state.unescape_name(name).unwrap_or_else(|| name.to_owned());
+    let res = state
+        .get_community(&GetCommunity {
+            name: Some(name),
+            auth: state.auth.clone(),
+            ..Default::default()
+        })
+        .await;
+    match res {
+        Ok(res) => Ok(Some(Info::Community(Box::new(res)))),
+        Err(e) if is_not_found(&e) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Posts linking to `url`
+async fn posts_linking(
+    state: &ResponseState<'_>,
+    url: &str,
+) -> Result<Vec<PostView>, server_config::ServerSideError> {
+    let res = state
+        .search(&Search {
+            q: url.to_owned(),
+            type_: Some(SearchType::Url),
+            listing_type: Some(ListingType::All),
+            limit: Some(INFO_URL_LIMIT),
+            auth: state.auth.clone(),
+            ..Default::default()
+        })
+        .await?;
+    // Lemmy also finds URLs containing `url`
+    let url = url.trim_end_matches('/');
+    Ok(res
+        .posts
+        .into_iter()
+        .filter(|pv| {
+            pv.post
+                .url
+                .as_ref()
+                .is_some_and(|u| u.as_str().trim_end_matches('/') == url)
+        })
+        .collect())
+}
+
+/// Translate looked up things to a listing, in the order they were requested
+async fn info_listing(
+    mut state: ResponseState<'_>,
+    things: Vec<Info>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    for info in &things {
+        match info {
+            Info::Post(_, moderators) => state.add_moderators(moderators),
+            Info::Community(res) => state.add_moderators(&res.moderators),
+            Info::Comment(_) => {}
+        }
+    }
+    state.load_my_user().await?;
+    let community_ids: Vec<CommunityId> = things
+        .iter()
+        .map(|info| match info {
+            Info::Post(pv, _) => pv.community.id,
+            Info::Comment(cv) => cv.community.id,
+            Info::Community(res) => res.community_view.community.id,
+        })
+        .collect();
+    state.load_removals(community_ids).await;
+    let texts: Vec<String> = things
+        .iter()
+        .filter_map(|info| match info {
+            Info::Post(pv, _) => pv.post.body.clone(),
+            Info::Comment(cv) => Some(cv.comment.content.clone()),
+            Info::Community(res) => res.community_view.community.description.clone(),
+        })
+        .collect();
+    state.resolve_links(texts.iter().map(String::as_str)).await;
+
+    let things = things
+        .into_iter()
+        .map(|info| match info {
+            Info::Post(pv, _) => RedditThing::Submission(api_translation::post(&state, *pv).data),
+            Info::Comment(cv) => RedditThing::Comment(api_translation::comment(&state, *cv).data),
+            Info::Community(res) => {
+                RedditThing::Subreddit(api_translation::community(&state, res.community_view).data)
+            }
+        })
+        .collect();
+    respond_json(&Listing::new(things))
+}
+
+/// Posts, comments and communities by fullname, posts linking to a URL or communities by name
+async fn info(
+    req: HttpRequest,
+    query: web::Query<api::InfoQuery>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let query = query.into_inner();
+
+    let mut things = vec![];
+    if let Some(ids) = &query.id {
+        let fullnames = ids
+            .split(',')
+            .filter_map(|id| id.trim().parse().ok())
+            .collect();
+        things.extend(lookup_fullnames(&state, fullnames).await?);
+    }
+    if let Some(names) = &query.sr_name {
+        let lookups = names
+            .split(',')
+            .map(|name| lookup_community(&state, name.trim()));
+        things.extend(
+            futures::future::try_join_all(lookups)
+                .await?
+                .into_iter()
+                .flatten(),
+        );
+    }
+    if let Some(url) = &query.url {
+        let posts = posts_linking(&state, url).await?;
+        things.extend(posts.into_iter().map(|pv| Info::Post(Box::new(pv), vec![])));
+    }
+    info_listing(state, things).await
+}
+
+/// Posts by fullname
+#[get("/by_id/{fullnames}")]
+async fn by_id(
+    req: HttpRequest,
+    path: web::Path<(String,)>,
+) -> Result<HttpResponse, server_config::ServerSideError> {
+    let state = prepare(&req)?;
+    let (fullnames,) = path.into_inner();
+    let fullnames = fullnames.strip_suffix(".json").unwrap_or(&fullnames);
+
+    let fullnames = fullnames
+        .split(',')
+        .filter_map(|fullname| Fullname::parse_as(ThingKind::Link, fullname.trim()).ok())
+        .filter(|fullname| fullname.kind == ThingKind::Link)
+        .collect();
+    let things = lookup_fullnames(&state, fullnames).await?;
+    info_listing(state, things).await
+}
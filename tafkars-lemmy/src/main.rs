@@ -5,7 +5,10 @@ use std::env;
 
 pub mod api_translation;
 pub mod endpoints;
+pub mod markdown_render;
+pub mod media;
 pub mod server_config;
+pub mod sidebar;
 pub use api_translation::*;
 pub use endpoints::*;
 pub use server_config::*;
@@ -21,6 +24,11 @@ async fn main() -> std::io::Result<()> {
 
     let app_state = AppState {
         http_client: Default::default(),
+        upload_leases: Default::default(),
+        captchas: Default::default(),
+        sessions: Default::default(),
+        known_instances: Default::default(),
+        resolved_links: Default::default(),
     };
 
     HttpServer::new(move || {
@@ -0,0 +1,260 @@
+//! Renders Markdown the way Lemmy does.
+//! Lemmy uses markdown-it with plugins for spoilers, superscript and subscript,
+//! none of which are supported by plain CommonMark.
+
+use markdown::{CompileOptions, Constructs, Options, ParseOptions};
+
+/// Render Lemmy-flavoured Markdown to HTML, wrapped in the `<div class="md">` container clients expect
+pub fn to_html(md: &str) -> String {
+    let md = unescape_quotes(md);
+    format!("<div class=\"md\">{}</div>", render_blocks(&md))
+}
+
+/// Some Lemmy versions store `>` as `&gt;`, which turns quotes into paragraphs that start with "&gt;".
+/// Turn them back into quote markers, except in fenced code.
+fn unescape_quotes(md: &str) -> String {
+    let mut in_fence = false;
+    md.lines()
+        .map(|line| {
+            if is_fence(line) {
+                in_fence = !in_fence;
+                return line.to_owned();
+            }
+            if in_fence {
+                return line.to_owned();
+            }
+            let indent = line.len() - line.trim_start().len();
+            let (indent, mut rest) = line.split_at(indent);
+            let mut unescaped = indent.to_owned();
+            while let Some(r) = rest.strip_prefix("&gt;").or_else(|| rest.strip_prefix('>')) {
+                unescaped.push('>');
+                let spaces = r.len() - r.trim_start().len();
+                unescaped.push_str(&r[..spaces]);
+                rest = &r[spaces..];
+            }
+            unescaped.push_str(rest);
+            unescaped
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Title of a `::: spoiler title` container
+fn spoiler_title(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix(":::")?.trim_start();
+    let title = rest.strip_prefix("spoiler")?;
+    (title.is_empty() || title.starts_with(char::is_whitespace)).then(|| title.trim())
+}
+
+fn is_fence(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+/// Render spoiler containers as `<details>` and everything in between as Markdown
+fn render_blocks(md: &str) -> String {
+    let mut html = String::new();
+    let mut chunk = String::new();
+    let mut in_fence = false;
+    let mut lines = md.lines();
+
+    while let Some(line) = lines.next() {
+        if is_fence(line) {
+            in_fence = !in_fence;
+        }
+        let title = match spoiler_title(line) {
+            Some(title) if !in_fence => title,
+            _ => {
+                chunk.push_str(line);
+                chunk.push('\n');
+                continue;
+            }
+        };
+
+        let mut inner = String::new();
+        let mut depth = 1;
+        for line in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed == ":::" {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            } else if trimmed.starts_with(":::") {
+                depth += 1;
+            }
+            inner.push_str(line);
+            inner.push('\n');
+        }
+
+        html.push_str(&render_markdown(&chunk));
+        chunk.clear();
+        html.push_str(&format!(
+            "<details><summary>{}</summary>\n{}</details>\n",
+            html_escape::encode_text(title),
+            render_blocks(&inner)
+        ));
+    }
+    html.push_str(&render_markdown(&chunk));
+    html
+}
+
+fn render_markdown(md: &str) -> String {
+    if md.trim().is_empty() {
+        return "".to_owned();
+    }
+
+    let options = Options {
+        parse: ParseOptions {
+            constructs: Constructs::gfm(),
+            // single tildes are subscript
+            gfm_strikethrough_single_tilde: false,
+            ..ParseOptions::default()
+        },
+        compile: CompileOptions::gfm(),
+    };
+    let html = markdown::to_html_with_options(md, &options).unwrap_or_default();
+    superscript_subscript(&html)
+}
+
+/// Turn `^sup^` and `~sub~` in text outside of code into `<sup>` and `<sub>` tags
+fn superscript_subscript(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut code_depth: usize = 0;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if rest.starts_with('<') {
+            let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+            let tag = &rest[..end];
+            if tag.starts_with("<code") || tag.starts_with("<pre") {
+                code_depth += 1;
+            } else if tag.starts_with("</code") || tag.starts_with("</pre") {
+                code_depth = code_depth.saturating_sub(1);
+            }
+            out.push_str(tag);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let end = rest.find('<').unwrap_or(rest.len());
+        let text = &rest[..end];
+        if code_depth == 0 {
+            out.push_str(&wrap_delimited(
+                &wrap_delimited(text, '^', "sup"),
+                '~',
+                "sub",
+            ));
+        } else {
+            out.push_str(text);
+        }
+        rest = &rest[end..];
+    }
+    out
+}
+
+/// Wrap text between two `delim`s in `tag`, if it is not empty and contains no whitespace
+fn wrap_delimited(text: &str, delim: char, tag: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(delim) {
+        let after = &rest[start + 1..];
+        match after.find(delim) {
+            Some(len) if len > 0 && !after[..len].contains(char::is_whitespace) => {
+                out.push_str(&rest[..start]);
+                out.push_str(&format!("<{tag}>{}</{tag}>", &after[..len]));
+                rest = &after[len + 1..];
+            }
+            _ => {
+                out.push_str(&rest[..=start]);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaped_quotes() {
+        assert_eq!(
+            unescape_quotes("&gt; quote\n&gt;&gt; nested"),
+            "> quote\n>> nested"
+        );
+        assert_eq!(
+            to_html("&gt; quote"),
+            "<div class=\"md\"><blockquote>\n<p>quote</p>\n</blockquote>\n</div>"
+        );
+        assert_eq!(unescape_quotes("a &gt; b"), "a &gt; b");
+    }
+
+    #[test]
+    fn quotes_in_code_fences_stay_escaped() {
+        let md = "```\n&gt; not a quote\n```\n&gt; quote";
+        assert_eq!(unescape_quotes(md), "```\n&gt; not a quote\n```\n> quote");
+        let md = "~~~rust\n&gt; x\n~~~";
+        assert_eq!(unescape_quotes(md), md);
+    }
+
+    #[test]
+    fn spoiler() {
+        let html = to_html("before\n\n::: spoiler Ending\nhe **dies**\n:::\n\nafter");
+        assert_eq!(
+            html,
+            "<div class=\"md\"><p>before</p>\n\
+             <details><summary>Ending</summary>\n<p>he <strong>dies</strong></p>\n</details>\n\
+             <p>after</p>\n</div>"
+        );
+    }
+
+    #[test]
+    fn spoiler_in_code_fence() {
+        let html = to_html("```\n::: spoiler x\n:::\n```");
+        assert!(!html.contains("<details>"), "{html}");
+    }
+
+    #[test]
+    fn nested_spoilers() {
+        let html = to_html("::: spoiler outer\n::: spoiler inner\nsecret\n:::\n:::");
+        assert_eq!(html.matches("<details>").count(), 2, "{html}");
+        assert!(html.contains("<summary>inner</summary>"), "{html}");
+    }
+
+    #[test]
+    fn superscript_and_subscript() {
+        assert_eq!(
+            to_html("E = mc^2^ and H~2~O"),
+            "<div class=\"md\"><p>E = mc<sup>2</sup> and H<sub>2</sub>O</p>\n</div>"
+        );
+        // with whitespace the delimiters are kept
+        assert_eq!(
+            to_html("a ^ b ^ c"),
+            "<div class=\"md\"><p>a ^ b ^ c</p>\n</div>"
+        );
+    }
+
+    #[test]
+    fn no_superscript_in_code() {
+        assert_eq!(
+            to_html("`x^2^`"),
+            "<div class=\"md\"><p><code>x^2^</code></p>\n</div>"
+        );
+        assert_eq!(
+            to_html("```\nH~2~O\n```"),
+            "<div class=\"md\"><pre><code>H~2~O\n</code></pre>\n</div>"
+        );
+    }
+
+    #[test]
+    fn double_tilde_is_strikethrough() {
+        assert_eq!(
+            to_html("~~gone~~"),
+            "<div class=\"md\"><p><del>gone</del></p>\n</div>"
+        );
+    }
+}
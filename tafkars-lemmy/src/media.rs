@@ -0,0 +1,260 @@
+//! Classifies the links of Lemmy posts and builds media related submission fields.
+//! Images hosted on pict-rs, Lemmy's image server, can be resized through its processing parameters.
+
+use reqwest::Url;
+use tafkars::submission::{
+    ImageSource, OEmbed, Preview, PreviewImage, PreviewVariant, PreviewVariants, RedditVideo,
+};
+
+/// Widths of the scaled down preview resolutions
+const PREVIEW_WIDTHS: [u32; 6] = [108, 216, 320, 640, 960, 1080];
+/// Blur radius for the previews of NSFW posts
+const NSFW_BLUR: f32 = 30.0;
+/// Size of embedded video players, 16:9
+pub const EMBED_WIDTH: u32 = 600;
+pub const EMBED_HEIGHT: u32 = 338;
+
+const IMAGE_EXTENSIONS: [&str; 7] = ["jpg", "jpeg", "png", "gif", "webp", "avif", "bmp"];
+const VIDEO_EXTENSIONS: [&str; 4] = ["mp4", "webm", "mov", "m4v"];
+
+fn extension(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let (_, ext) = url.path().rsplit_once('.')?;
+    Some(ext.to_lowercase())
+}
+
+pub fn is_image(url: &str) -> bool {
+    extension(url).is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()))
+}
+
+pub fn is_video(url: &str) -> bool {
+    extension(url).is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.as_str()))
+}
+
+pub fn is_gif(url: &str) -> bool {
+    extension(url).is_some_and(|ext| ext == "gif")
+}
+
+/// Is this file hosted on a pict-rs server?
+pub fn is_pictrs(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| url.path().starts_with("/pictrs/image/"))
+}
+
+/// The host of `url` without a leading `www.`
+pub fn domain(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    Some(host.strip_prefix("www.").unwrap_or(host).to_owned())
+}
+
+/// Add pict-rs processing parameters, e.g. `thumbnail=320`, to a pict-rs URL
+pub fn pictrs_process(url: &str, params: &str) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{url}{separator}{params}")
+}
+
+/// The reddit `post_hint` for a post linking to `url`, `embedded` if Lemmy found an embeddable video player there
+pub fn post_hint(url: Option<&str>, embedded: bool) -> &'static str {
+    match url {
+        None => "self",
+        Some(url) if is_image(url) => "image",
+        Some(url) if is_video(url) => "hosted:video",
+        Some(_) if embedded => "rich:video",
+        Some(_) => "link",
+    }
+}
+
+/// A directly linked video file, presented as a hosted video so that clients play it inline
+pub fn reddit_video(url: &str) -> RedditVideo {
+    RedditVideo {
+        fallback_url: url.to_owned(),
+        is_gif: false,
+        transcoding_status: Some("completed".to_owned()),
+        ..Default::default()
+    }
+}
+
+/// HTML for an iframe showing the video player at `embed_url`
+pub fn embed_iframe(embed_url: &str) -> String {
+    format!(
+        "<iframe width=\"{EMBED_WIDTH}\" height=\"{EMBED_HEIGHT}\" src=\"{}\" frameborder=\"0\" allowfullscreen></iframe>",
+        html_escape::encode_double_quoted_attribute(embed_url)
+    )
+}
+
+/// oEmbed metadata for media embedded from `embed_url`, `html` being the (possibly escaped) iframe
+pub fn oembed(
+    embed_url: &str,
+    title: Option<String>,
+    description: Option<String>,
+    html: Option<String>,
+    thumbnail_url: Option<String>,
+) -> OEmbed {
+    let provider_url = Url::parse(embed_url)
+        .ok()
+        .map(|url| format!("{}://{}/", url.scheme(), url.host_str().unwrap_or_default()));
+
+    OEmbed {
+        type_: if html.is_some() { "video" } else { "link" }.to_owned(),
+        version: Some("1.0".to_owned()),
+        title,
+        description,
+        provider_name: domain(embed_url),
+        provider_url,
+        width: html.is_some().then_some(EMBED_WIDTH),
+        height: html.is_some().then_some(EMBED_HEIGHT),
+        html,
+        thumbnail_url,
+        ..Default::default()
+    }
+}
+
+/// `&` in preview URLs is HTML-escaped like reddit does, unless `raw_json=1` was requested
+fn escape_url(url: String, raw_json: bool) -> String {
+    if raw_json {
+        url
+    } else {
+        url.replace('&', "&amp;")
+    }
+}
+
+/// Scaled down versions of `url` generated by pict-rs, empty for images hosted elsewhere.
+/// pict-rs fits thumbnails into a square, so only the maximum width is known.
+fn resolutions(url: &str, extra_params: &str, raw_json: bool) -> Vec<ImageSource> {
+    if !is_pictrs(url) {
+        return vec![];
+    }
+    PREVIEW_WIDTHS
+        .iter()
+        .map(|&width| ImageSource {
+            url: escape_url(
+                pictrs_process(url, &format!("thumbnail={width}{extra_params}")),
+                raw_json,
+            ),
+            width: Some(width),
+            height: None,
+        })
+        .collect()
+}
+
+fn blurred(url: &str, raw_json: bool) -> Option<PreviewVariant> {
+    is_pictrs(url).then(|| PreviewVariant {
+        source: ImageSource {
+            url: escape_url(pictrs_process(url, &format!("blur={NSFW_BLUR}")), raw_json),
+            ..Default::default()
+        },
+        resolutions: resolutions(url, &format!("&blur={NSFW_BLUR}"), raw_json),
+    })
+}
+
+/// Preview of the image at `image_url`, `enabled` if the image is the post's main content.
+/// Lemmy doesn't know the size of images, so it is left out of the sources.
+pub fn preview(image_url: &str, enabled: bool, nsfw: bool, raw_json: bool) -> Preview {
+    let source = || ImageSource {
+        url: escape_url(image_url.to_owned(), raw_json),
+        ..Default::default()
+    };
+    let gif = is_gif(image_url).then(|| PreviewVariant {
+        source: source(),
+        resolutions: vec![],
+    });
+
+    let image = PreviewImage {
+        source: source(),
+        resolutions: resolutions(image_url, "", raw_json),
+        variants: PreviewVariants {
+            gif,
+            mp4: None,
+            obfuscated: if nsfw {
+                blurred(image_url, raw_json)
+            } else {
+                None
+            },
+            nsfw: if nsfw {
+                blurred(image_url, raw_json)
+            } else {
+                None
+            },
+        },
+        id: image_url
+            .rsplit('/')
+            .next()
+            .and_then(|file| file.split(['.', '?']).next())
+            .unwrap_or_default()
+            .to_owned(),
+    };
+
+    Preview {
+        images: vec![image],
+        enabled,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PICTRS: &str = "https://lemmy.example/pictrs/image/abc.png";
+
+    #[test]
+    fn classify_urls() {
+        assert!(is_image("https://example.com/a/cat.JPG?size=2"));
+        assert!(!is_image("https://example.com/jpg"));
+        assert!(is_video("https://example.com/clip.webm"));
+        assert!(!is_video("not a url.mp4"));
+        assert!(is_pictrs(PICTRS));
+    }
+
+    #[test]
+    fn post_hints() {
+        assert_eq!(post_hint(None, false), "self");
+        assert_eq!(post_hint(Some(PICTRS), true), "image");
+        assert_eq!(
+            post_hint(Some("https://example.com/a.mp4"), false),
+            "hosted:video"
+        );
+        assert_eq!(
+            post_hint(Some("https://youtube.com/watch?v=1"), true),
+            "rich:video"
+        );
+        assert_eq!(post_hint(Some("https://example.com/"), false), "link");
+    }
+
+    #[test]
+    fn preview_sizes() {
+        let preview = preview(PICTRS, true, false, true);
+        let image = &preview.images[0];
+        assert_eq!(image.id, "abc");
+        assert_eq!(image.resolutions.len(), PREVIEW_WIDTHS.len());
+        assert_eq!(image.resolutions[0].url, format!("{PICTRS}?thumbnail=108"));
+
+        let json = serde_json::to_value(image).unwrap();
+        assert_eq!(json["source"], serde_json::json!({ "url": PICTRS }));
+        assert_eq!(json["resolutions"][0]["width"], 108);
+        assert!(json["resolutions"][0].get("height").is_none());
+        assert!(json["variants"].get("nsfw").is_none());
+    }
+
+    #[test]
+    fn preview_escapes_urls() {
+        let preview = preview(PICTRS, true, true, false);
+        let nsfw = preview.images[0].variants.nsfw.as_ref().unwrap();
+        assert_eq!(nsfw.source.url, format!("{PICTRS}?blur=30"));
+        assert_eq!(
+            nsfw.resolutions[0].url,
+            format!("{PICTRS}?thumbnail=108&amp;blur=30")
+        );
+        assert!(!preview.images[0].resolutions.is_empty());
+        assert!(preview.images[0].variants.gif.is_none());
+    }
+
+    #[test]
+    fn preview_of_external_image() {
+        let preview = preview("https://example.com/cat.gif?a=1&b=2", false, true, false);
+        let image = &preview.images[0];
+        assert_eq!(image.source.url, "https://example.com/cat.gif?a=1&amp;b=2");
+        assert!(image.resolutions.is_empty());
+        assert!(image.variants.gif.is_some());
+        assert!(image.variants.nsfw.is_none());
+    }
+}
@@ -1,3 +1,4 @@
+use actix_web::http::StatusCode;
 use actix_web::ResponseError;
 
 use std::num::ParseIntError;
@@ -18,6 +19,30 @@ pub enum ServerSideError {
     ParseIntError(#[from] ParseIntError),
     #[error("Misconfigured gateway")]
     MisconfigurationError,
+    #[error("Lemmy error: {0}")]
+    LemmyError(String),
+    #[error("not logged in")]
+    NotLoggedIn,
+    #[error("missing or wrong modhash")]
+    InvalidModhash,
+    #[error("bad request: {0}")]
+    BadRequest(String),
+    #[error("not found")]
+    NotFound,
+    #[error("upload error: {0}")]
+    MultipartError(#[from] actix_multipart::MultipartError),
 }
 
-impl ResponseError for ServerSideError {}
+impl ResponseError for ServerSideError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ServerSideError::LemmyError(_)
+            | ServerSideError::BadRequest(_)
+            | ServerSideError::MultipartError(_) => StatusCode::BAD_REQUEST,
+            ServerSideError::NotLoggedIn => StatusCode::UNAUTHORIZED,
+            ServerSideError::InvalidModhash => StatusCode::FORBIDDEN,
+            ServerSideError::NotFound => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
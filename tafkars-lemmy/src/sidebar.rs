@@ -0,0 +1,267 @@
+//! Extracts structured information from the Markdown sidebar of a Lemmy community,
+//! which is all Lemmy has in place of subreddit rules, wiki and public description.
+
+/// Maximum length of a rule's `short_name`
+const MAX_SHORT_NAME_LEN: usize = 100;
+/// Maximum length of a subreddit's `public_description`
+const MAX_SUMMARY_LEN: usize = 500;
+
+/// A rule found in a community sidebar
+#[derive(Debug, PartialEq)]
+pub struct SidebarRule {
+    /// Plain text title of the rule
+    pub short_name: String,
+    /// Markdown description of the rule, can be empty
+    pub description: String,
+}
+
+/// Find the community rules in a sidebar.
+/// Uses the list following a heading containing "rule", or the first numbered list in the sidebar otherwise.
+pub fn rules(md: &str) -> Vec<SidebarRule> {
+    let lines: Vec<&str> = md.lines().collect();
+
+    let rules_section = lines.iter().enumerate().find_map(|(i, line)| {
+        let level = heading_level(line)?;
+        line.to_lowercase()
+            .contains("rule")
+            .then_some((i + 1, level))
+    });
+
+    let items = match rules_section {
+        Some((start, level)) => {
+            let end = lines[start..]
+                .iter()
+                .position(|l| heading_level(l).is_some_and(|l| l <= level))
+                .map_or(lines.len(), |e| start + e);
+            list_items(&lines[start..end], false)
+        }
+        None => list_items(&lines, true),
+    };
+
+    items
+        .into_iter()
+        .filter_map(|item| rule_from_item(&item))
+        .collect()
+}
+
+/// A plain text, one line summary of a sidebar, made from its first paragraph of text
+pub fn summary(md: &str) -> String {
+    let paragraph: Vec<&str> = md
+        .lines()
+        .map(str::trim)
+        .skip_while(|l| !is_text(l))
+        .take_while(|l| is_text(l))
+        .collect();
+
+    truncate(&strip_markdown(&paragraph.join(" ")), MAX_SUMMARY_LEN)
+}
+
+/// ATX headings (`## Rules`) have their usual level,
+/// lines that are completely bold (`**Rules**`) are treated as headings of the lowest level
+fn heading_level(line: &str) -> Option<usize> {
+    let line = line.trim();
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&hashes) && line[hashes..].starts_with(' ') {
+        return Some(hashes);
+    }
+
+    let inner = line
+        .strip_prefix("**")
+        .and_then(|l| l.strip_suffix("**"))
+        .or_else(|| line.strip_prefix("__").and_then(|l| l.strip_suffix("__")))?;
+    (!inner.is_empty() && !inner.contains("**")).then_some(6)
+}
+
+/// If `line` starts a list item, return its indentation, whether it is numbered and its text
+fn list_marker(line: &str) -> Option<(usize, bool, &str)> {
+    let indent = line.len() - line.trim_start().len();
+    let trimmed = line.trim_start();
+
+    if let Some(rest) = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("+ "))
+    {
+        return Some((indent, false, rest));
+    }
+
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    let rest = &trimmed[digits..];
+    if digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        return Some((indent, true, &rest[2..]));
+    }
+    None
+}
+
+/// Collect the top level items of the first list in `lines`, including their continuation lines
+fn list_items(lines: &[&str], numbered_only: bool) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+    let mut list_indent = None;
+    let mut after_blank = false;
+
+    for line in lines {
+        let marker = list_marker(line);
+        match (list_indent, marker) {
+            (None, Some((indent, numbered, text))) if numbered || !numbered_only => {
+                list_indent = Some(indent);
+                items.push(text.trim().to_owned());
+            }
+            (None, _) => {}
+            (Some(list_indent), Some((indent, _, text))) if indent <= list_indent => {
+                items.push(text.trim().to_owned());
+            }
+            (Some(list_indent), _) => {
+                let indent = line.len() - line.trim_start().len();
+                if line.trim().is_empty() {
+                    after_blank = true;
+                    continue;
+                }
+                if after_blank && indent <= list_indent {
+                    break; // a paragraph after the list
+                }
+                if heading_level(line).is_some() && indent <= list_indent {
+                    break;
+                }
+                let item = items.last_mut().expect("list has items");
+                item.push('\n');
+                item.push_str(line.trim());
+            }
+        }
+        after_blank = false;
+    }
+    items
+}
+
+/// Split a list item into a title and a description.
+/// A bold prefix (`**No spam** - don't post ads`) is the title, otherwise the first line is.
+fn rule_from_item(item: &str) -> Option<SidebarRule> {
+    let (title, description) = match item.strip_prefix("**").and_then(|i| i.split_once("**")) {
+        Some((title, rest)) => (
+            title,
+            rest.trim_start_matches(|c: char| c.is_whitespace() || ":-–—.".contains(c)),
+        ),
+        None => item.split_once('\n').unwrap_or((item, "")),
+    };
+
+    let short_name = truncate(&strip_markdown(title), MAX_SHORT_NAME_LEN);
+    if short_name.is_empty() {
+        return None;
+    }
+    Some(SidebarRule {
+        short_name,
+        description: description.trim().to_owned(),
+    })
+}
+
+/// Is this line part of a paragraph of plain text?
+fn is_text(line: &str) -> bool {
+    !line.is_empty()
+        && heading_level(line).is_none()
+        && list_marker(line).is_none()
+        && !line.starts_with('>')
+        && !line.starts_with('|')
+        && !line.starts_with("![")
+        && !line.starts_with("```")
+        && !line.starts_with(":::")
+        && line.chars().any(char::is_alphanumeric)
+        && !line.chars().all(|c| "-*_= ".contains(c))
+}
+
+/// Remove emphasis, code and link markup, keeping link texts
+fn strip_markdown(md: &str) -> String {
+    let mut out = String::with_capacity(md.len());
+    let mut rest = md;
+    while let Some(c) = rest.chars().next() {
+        if c == '[' {
+            // [text](url) -> text
+            if let Some((text, after)) = rest[1..].split_once("](") {
+                if let Some(close) = after.find(')') {
+                    out.push_str(text);
+                    rest = &after[close + 1..];
+                    continue;
+                }
+            }
+        }
+        if !"*_`~#".contains(c) {
+            out.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn truncate(text: &str, max_len: usize) -> String {
+    if text.chars().count() <= max_len {
+        return text.to_owned();
+    }
+    let mut truncated: String = text.chars().take(max_len - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn short_names(md: &str) -> Vec<String> {
+        rules(md).into_iter().map(|r| r.short_name).collect()
+    }
+
+    #[test]
+    fn numbered_list() {
+        let md = "Welcome to the community!\n\n1. Be nice\n2. No spam\n   Ads will be removed\n3. **Stay on topic**: only posts about Rust\n\nHave fun";
+        let rules = rules(md);
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[0].short_name, "Be nice");
+        assert_eq!(rules[1].short_name, "No spam");
+        assert_eq!(rules[1].description, "Ads will be removed");
+        assert_eq!(rules[2].short_name, "Stay on topic");
+        assert_eq!(rules[2].description, "only posts about Rust");
+    }
+
+    #[test]
+    fn bullets_without_heading_are_not_rules() {
+        assert!(rules("Links:\n- [Website](https://example.com)\n- Matrix").is_empty());
+    }
+
+    #[test]
+    fn rules_heading() {
+        let md = "# About\n- not a rule\n\n## Rules\n- Be nice\n- [Follow the CoC](https://example.com/coc)\n\n## Links\n1. not a rule either";
+        assert_eq!(short_names(md), ["Be nice", "Follow the CoC"]);
+    }
+
+    #[test]
+    fn bold_heading() {
+        let md = "**Community rules**\n\n* No memes\n* No reposts";
+        assert_eq!(short_names(md), ["No memes", "No reposts"]);
+    }
+
+    #[test]
+    fn nested_lists_belong_to_their_item() {
+        let md = "## Rules\n1. No NSFW\n   - this includes gore\n2. English only";
+        let rules = rules(md);
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].description, "- this includes gore");
+    }
+
+    #[test]
+    fn missing_rules() {
+        assert!(rules("").is_empty());
+        assert!(rules("Just a community about cats.\n\n## Links\nNone yet").is_empty());
+        assert!(rules("## Rules\nBe excellent to each other").is_empty());
+    }
+
+    #[test]
+    fn summary_of_first_paragraph() {
+        let md = "# Cats\n![banner](https://example.com/cat.png)\n\nA place for **cat** pictures,\nsee [the wiki](https://example.com).\n\nSecond paragraph";
+        assert_eq!(summary(md), "A place for cat pictures, see the wiki.");
+        assert_eq!(summary("## Rules\n1. Be nice"), "");
+    }
+
+    #[test]
+    fn truncated_summary() {
+        let summary = summary(&"word ".repeat(200));
+        assert_eq!(summary.chars().count(), MAX_SUMMARY_LEN);
+        assert!(summary.ends_with('…'));
+    }
+}
@@ -0,0 +1,297 @@
+//! # Parameters and responses of the `/api` endpoints that modify things
+use crate::fullname::Fullname;
+use serde::{Deserialize, Serialize};
+
+/// Response of endpoints called with `api_type=json`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonResponse<T> {
+    pub json: JsonResponseData<T>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonResponseData<T> {
+    /// Errors that occurred, empty on success.
+    pub errors: Vec<ApiError>,
+    /// The result of the request, if it succeeded and returns anything.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+}
+
+/// An error in a `JsonResponse`, serialized as an array of an error code like `USERNAME_TAKEN`,
+/// a human readable message and the name of the form field that caused the error, if any.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiError(pub String, pub String, pub Option<String>);
+
+/// `data` of responses that return created or modified things
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Things<T> {
+    pub things: Vec<T>,
+}
+
+impl<T> JsonResponse<T> {
+    pub fn data(data: T) -> Self {
+        Self {
+            json: JsonResponseData {
+                errors: vec![],
+                data: Some(data),
+            },
+        }
+    }
+
+    pub fn errors(errors: Vec<ApiError>) -> Self {
+        Self {
+            json: JsonResponseData { errors, data: None },
+        }
+    }
+}
+
+/// How to distinguish a thing
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DistinguishHow {
+    /// Distinguish as moderator
+    Yes,
+    /// Remove distinction
+    No,
+    /// Distinguish as admin
+    Admin,
+    /// Distinguish with a special distinction
+    Special,
+}
+
+#[derive(Debug, Deserialize)]
+/// Form of the `/api/distinguish` endpoint
+pub struct Distinguish {
+    /// fullname of the thing to distinguish
+    pub id: Fullname,
+    pub how: DistinguishHow,
+    /// sticky the comment to the top of the submission, only for top level comments
+    pub sticky: Option<bool>,
+}
+
+/// Response of endpoints that don't return anything
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Empty {}
+
+#[derive(Debug, Deserialize)]
+/// Form of endpoints acting on a single thing, like `/api/approve` and `/api/lock`
+pub struct ThingId {
+    /// fullname of the thing
+    pub id: Fullname,
+}
+
+#[derive(Debug, Deserialize)]
+/// Form of the `/api/remove` endpoint
+pub struct Remove {
+    /// fullname of the thing to remove
+    pub id: Fullname,
+    /// remove as spam
+    #[serde(default)]
+    pub spam: bool,
+}
+
+#[derive(Debug, Deserialize)]
+/// Form of the `/api/set_subreddit_sticky` endpoint
+pub struct SetSubredditSticky {
+    /// fullname of the submission
+    pub id: Fullname,
+    /// sticky or unsticky the submission
+    pub state: bool,
+    /// slot to sticky the submission in, 1 or 2
+    pub num: Option<u32>,
+    /// sticky to the author's profile instead of the subreddit
+    pub to_profile: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+/// Form of the `/api/report` endpoint
+pub struct Report {
+    /// fullname of the thing to report
+    pub thing_id: Fullname,
+    pub reason: Option<String>,
+    /// reason given when `reason` is "other"
+    pub other_reason: Option<String>,
+    pub rule_reason: Option<String>,
+    pub site_reason: Option<String>,
+    pub custom_text: Option<String>,
+}
+
+impl Report {
+    /// The most specific reason given for the report
+    pub fn reason(&self) -> String {
+        [
+            &self.other_reason,
+            &self.custom_text,
+            &self.rule_reason,
+            &self.site_reason,
+            &self.reason,
+        ]
+        .into_iter()
+        .flatten()
+        .find(|r| !r.trim().is_empty())
+        .cloned()
+        .unwrap_or("other".to_owned())
+    }
+}
+
+/// Kinds of relationships between users and with subreddits
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum RelationshipType {
+    #[serde(rename = "friend")]
+    Friend,
+    #[serde(rename = "enemy")]
+    Enemy,
+    #[serde(rename = "moderator")]
+    Moderator,
+    #[serde(rename = "moderator_invite")]
+    ModeratorInvite,
+    #[serde(rename = "contributor")]
+    Contributor,
+    #[serde(rename = "banned")]
+    Banned,
+    #[serde(rename = "muted")]
+    Muted,
+    #[serde(rename = "wikibanned")]
+    WikiBanned,
+    #[serde(rename = "wikicontributor")]
+    WikiContributor,
+}
+
+#[derive(Debug, Deserialize)]
+/// Form of the `/api/friend` endpoint
+pub struct Friend {
+    /// name of the user
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: RelationshipType,
+    /// fullname of the subreddit, for subreddit relationships like bans
+    pub container: Option<Fullname>,
+    /// duration of a ban in days, permanent if not set
+    pub duration: Option<u32>,
+    /// reason for a ban, usually one of the subreddit's rules
+    pub ban_reason: Option<String>,
+    /// message sent to a banned user
+    pub ban_message: Option<String>,
+    /// note left by a moderator
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+/// Form of the `/api/unfriend` endpoint, identifying the user by `name` or by fullname in `id`
+pub struct Unfriend {
+    pub name: Option<String>,
+    pub id: Option<Fullname>,
+    #[serde(rename = "type")]
+    pub type_: RelationshipType,
+    /// fullname of the subreddit, for subreddit relationships like bans
+    pub container: Option<Fullname>,
+}
+
+#[derive(Debug, Deserialize)]
+/// Form of the `/api/block_user` endpoint, identifying the user by fullname in `account_id` or by `name`
+pub struct BlockUser {
+    pub account_id: Option<Fullname>,
+    pub name: Option<String>,
+}
+
+/// A subreddit in a multireddit or filter, e.g. the response of `PUT /api/filter/{filterpath}/r/{srname}`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MultiSubreddit {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+/// Form of the `/api/media/asset.json` endpoint, requesting a lease to upload a file
+pub struct MediaAssetRequest {
+    /// file name of the upload
+    pub filepath: String,
+    /// MIME type of the upload, e.g. `image/png`
+    pub mimetype: String,
+}
+
+/// Response of `/api/media/asset.json`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MediaAsset {
+    /// Where and how to upload the file
+    pub args: UploadLease,
+    pub asset: Asset,
+}
+
+/// The form to upload a file with: the file has to be POSTed to `action` as `multipart/form-data`,
+/// together with all `fields`. The response is XML containing the URL of the file in `Location`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UploadLease {
+    /// URL to upload to, without a scheme, e.g. `//reddit-uploaded-media.s3-accelerate.amazonaws.com`
+    pub action: String,
+    pub fields: Vec<UploadField>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UploadField {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Asset {
+    pub asset_id: String,
+    pub processing_state: String,
+    pub payload: AssetPayload,
+    /// Websocket notifying about the processing of uploaded media
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub websocket_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssetPayload {
+    pub filepath: String,
+}
+
+/// Form of the `/api/register` endpoint
+#[derive(Debug, Deserialize)]
+pub struct Register {
+    pub user: String,
+    pub passwd: String,
+    /// password confirmation, some clients only send `passwd`
+    pub passwd2: Option<String>,
+    pub email: Option<String>,
+    /// the `iden` of the captcha from `/api/new_captcha`
+    pub iden: Option<String>,
+    /// answer to the captcha
+    pub captcha: Option<String>,
+    /// answer to the registration application question, not part of reddit's API
+    pub answer: Option<String>,
+}
+
+/// `data` of `/api/new_captcha`, the captcha image is at `/captcha/{iden}.png`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Captcha {
+    pub iden: String,
+}
+
+/// `data` of `/api/register` and `/api/login`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Session {
+    pub need_https: bool,
+    pub modhash: String,
+    /// value of the `reddit_session` cookie
+    pub cookie: String,
+}
+
+/// Form of the `/api/login` endpoint
+#[derive(Debug, Deserialize)]
+pub struct LoginForm {
+    pub user: String,
+    pub passwd: String,
+}
+
+/// Query of the `/api/info` endpoint, things can be looked up by one of the fields
+#[derive(Debug, Deserialize)]
+pub struct InfoQuery {
+    /// comma separated fullnames of things, e.g. `t3_1,t1_2,t5_3`
+    pub id: Option<String>,
+    /// find submissions linking to this URL
+    pub url: Option<String>,
+    /// comma separated subreddit names
+    pub sr_name: Option<String>,
+}
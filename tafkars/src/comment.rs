@@ -1,10 +1,16 @@
 //! # Subreddit Comment Responses
-use crate::listing::{Listing, ListingData};
+use crate::fullname::Fullname;
+use crate::listing::Listing;
+use crate::submission::{Edited, ModReport, UserReport};
 use serde::{Deserialize, Serialize};
 
 /// SubredditCommentsData  
 /// Everything is an option to deal with both `latest_comments` and `article_comments`
 #[derive(Serialize, Debug, Deserialize, Default)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra")),
+    serde(deny_unknown_fields)
+)]
 pub struct CommentData {
     /// Total awards
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -12,7 +18,7 @@ pub struct CommentData {
     /// Approved at (UTC)
     pub approved_at_utc: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub link_id: Option<String>,
+    pub link_id: Option<Fullname>,
     /// What is this
     pub author_flair_template_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -36,18 +42,36 @@ pub struct CommentData {
     pub can_mod_post: Option<bool>,
     /// Created (UTC)
     pub created_utc: Option<f64>,
+    /// Edited
+    #[serde(default)]
+    pub edited: Edited,
     /// Send replies
     pub send_replies: Option<bool>,
     /// Parent ID
-    pub parent_id: Option<String>,
+    pub parent_id: Option<Fullname>,
     /// Score
     pub score: Option<i32>,
     /// Author fullname
-    pub author_fullname: Option<String>,
+    pub author_fullname: Option<Fullname>,
     /// Over 18
     pub over_18: Option<bool>,
     /// Approved by
     pub approved_by: Option<String>,
+    /// Removed (moderators only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed: Option<bool>,
+    /// Approved (moderators only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved: Option<bool>,
+    /// Removed by (moderators only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub banned_by: Option<String>,
+    /// Removed at (UTC, moderators only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub banned_at_utc: Option<f64>,
+    /// Removal reason (moderators only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removal_reason: Option<String>,
     /// Subreddit ID
     pub subreddit_id: Option<String>,
     /// Body
@@ -55,7 +79,7 @@ pub struct CommentData {
     /// Link title
     pub link_title: Option<String>,
     /// Name
-    pub name: Option<String>,
+    pub name: Option<Fullname>,
     /// Patreon flair
     pub author_patreon_flair: Option<bool>,
     /// Downs?
@@ -83,6 +107,18 @@ pub struct CommentData {
     pub permalink: Option<String>,
     /// Number of reports
     pub num_reports: Option<i32>,
+    /// User reports (moderators only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_reports: Option<Vec<UserReport>>,
+    /// Moderator reports (moderators only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mod_reports: Option<Vec<ModReport>>,
+    /// Report reasons (moderators only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report_reasons: Option<Vec<String>>,
+    /// Ignore reports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_reports: Option<bool>,
     /// Permalink
     pub link_permalink: Option<String>,
     /// Author link
@@ -110,8 +146,23 @@ pub struct CommentData {
     pub subreddit_type: Option<String>,
     /// UPS?
     pub ups: Option<i32>,
-    /// Replies
-    pub replies: Option<MaybeReplies>,
+    /// Replies, `""` if there are none
+    #[serde(default, with = "crate::empty_string")]
+    pub replies: Option<Listing<Comment>>,
+    #[cfg(feature = "extra")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl CommentData {
+    /// The replies to this comment, for adding more
+    pub fn replies_mut(&mut self) -> &mut Vec<Comment> {
+        &mut self
+            .replies
+            .get_or_insert_with(|| Listing::new(vec![]))
+            .data
+            .children
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -119,42 +170,143 @@ pub struct CommentData {
 pub struct Comment {
     pub data: CommentData,
 }
-// TODO: this is super ugly and should just be Option<Listing<Comment>> with serde tricks to use "" as None
-/// Replies can be more comments or an empty string
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum MaybeReplies {
-    /// Reply
-    Reply(Listing<Comment>),
-    /// String
-    Str(String),
-}
 
-impl MaybeReplies {
-    pub fn replies(&mut self) -> &mut Vec<Comment> {
-        match self {
-            MaybeReplies::Reply(l) => &mut l.data.children,
-            MaybeReplies::Str(_) => {
-                *self = MaybeReplies::Reply(Listing {
-                    data: ListingData {
-                        modhash: None,
-                        dist: None,
-                        after: None,
-                        before: None,
-                        children: vec![],
-                    },
-                });
-                match self {
-                    MaybeReplies::Reply(l) => &mut l.data.children,
-                    _ => unreachable!(),
-                }
+#[allow(deprecated)]
+pub use maybe_replies::MaybeReplies;
+
+// The code derived for the deprecated type uses it, which can only be allowed around the whole type
+#[allow(deprecated)]
+mod maybe_replies {
+    use super::Comment;
+    use crate::listing::Listing;
+    use serde::{Deserialize, Serialize};
+
+    /// Replies can be more comments or an empty string
+    #[deprecated(
+        note = "`CommentData::replies` is an `Option<Listing<Comment>>` now, `\"\"` is `None`"
+    )]
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(untagged)]
+    pub enum MaybeReplies {
+        /// Reply
+        Reply(Listing<Comment>),
+        /// String
+        Str(String),
+    }
+
+    impl MaybeReplies {
+        pub fn replies(&mut self) -> &mut Vec<Comment> {
+            if let MaybeReplies::Str(_) = self {
+                *self = MaybeReplies::Reply(Listing::new(vec![]));
+            }
+            match self {
+                MaybeReplies::Reply(l) => &mut l.data.children,
+                MaybeReplies::Str(_) => unreachable!(),
             }
         }
     }
+
+    impl Default for MaybeReplies {
+        fn default() -> Self {
+            MaybeReplies::Str("".to_owned())
+        }
+    }
+
+    impl From<MaybeReplies> for Option<Listing<Comment>> {
+        fn from(replies: MaybeReplies) -> Self {
+            match replies {
+                MaybeReplies::Reply(l) => Some(l),
+                MaybeReplies::Str(_) => None,
+            }
+        }
+    }
+
+    impl From<Option<Listing<Comment>>> for MaybeReplies {
+        fn from(replies: Option<Listing<Comment>>) -> Self {
+            replies.map_or_else(Default::default, MaybeReplies::Reply)
+        }
+    }
 }
 
-impl Default for MaybeReplies {
-    fn default() -> Self {
-        MaybeReplies::Str("".to_owned())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    /// Parse a comment and serialize it again
+    fn round_trip(comment: Value) -> (CommentData, Value) {
+        let data: CommentData = serde_json::from_value(comment).unwrap();
+        let json = serde_json::to_value(&data).unwrap();
+        let again: CommentData = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(again.replies.is_some(), data.replies.is_some());
+        (data, json)
+    }
+
+    #[test]
+    fn no_replies() {
+        for comment in [json!({"replies": ""}), json!({"replies": null}), json!({})] {
+            let (data, json) = round_trip(comment);
+            assert!(data.replies.is_none());
+            assert_eq!(json["replies"], "");
+        }
+        let data: CommentData = serde_json::from_str(r#"{"replies": ""}"#).unwrap();
+        assert!(data.replies.is_none());
+    }
+
+    #[test]
+    fn nested_replies() {
+        let comment = json!({
+            "id": "a",
+            "replies": {
+                "kind": "Listing",
+                "data": {
+                    "children": [{
+                        "kind": "t1",
+                        "data": {"id": "b", "replies": ""},
+                    }],
+                },
+            },
+        });
+        let (data, json) = round_trip(comment);
+        let replies = data.replies.unwrap().data.children;
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].data.id.as_deref(), Some("b"));
+        assert!(replies[0].data.replies.is_none());
+
+        let reply = &json["replies"]["data"]["children"][0];
+        assert_eq!(reply["kind"], "t1");
+        assert_eq!(reply["data"]["id"], "b");
+        assert_eq!(reply["data"]["replies"], "");
+    }
+
+    #[test]
+    fn invalid_replies_keep_their_error() {
+        let comment = json!({"replies": {"kind": "Listing"}});
+        let err = serde_json::from_value::<CommentData>(comment).unwrap_err();
+        assert!(err.to_string().contains("missing field `data`"), "{err}");
+
+        let comment = json!({"replies": "more"});
+        assert!(serde_json::from_value::<CommentData>(comment).is_err());
+    }
+
+    #[cfg(feature = "extra")]
+    #[test]
+    fn extra_fields_round_trip() {
+        let comment = json!({"id": "a", "gildings": {"gid_1": 2}, "treatment_tags": []});
+        let (data, json) = round_trip(comment);
+        assert_eq!(data.extra["gildings"], json!({"gid_1": 2}));
+        assert_eq!(json["gildings"]["gid_1"], 2);
+        assert_eq!(json["treatment_tags"], json!([]));
+    }
+
+    #[cfg(all(feature = "strict", not(feature = "extra")))]
+    #[test]
+    fn strict_rejects_unknown_fields() {
+        let err = serde_json::from_value::<CommentData>(json!({"gildings": {}})).unwrap_err();
+        assert!(
+            err.to_string().contains("unknown field `gildings`"),
+            "{err}"
+        );
+        assert!(serde_json::from_value::<CommentData>(json!({"id": "a"})).is_ok());
     }
 }
@@ -0,0 +1,298 @@
+//! Fullnames identify things by kind and base-36 ID, like `t3_15bfi0`.
+//! See: <https://www.reddit.com/dev/api/#fullnames>
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// The kind of a thing, the prefix of its fullname
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThingKind {
+    /// `t1`
+    Comment,
+    /// `t2`
+    Account,
+    /// `t3`
+    Link,
+    /// `t4`
+    Message,
+    /// `t5`
+    Subreddit,
+    /// `t6`
+    Award,
+    /// `more`, a placeholder for comments that weren't loaded
+    More,
+    Listing,
+}
+
+impl ThingKind {
+    pub fn prefix(self) -> &'static str {
+        match self {
+            ThingKind::Comment => "t1",
+            ThingKind::Account => "t2",
+            ThingKind::Link => "t3",
+            ThingKind::Message => "t4",
+            ThingKind::Subreddit => "t5",
+            ThingKind::Award => "t6",
+            ThingKind::More => "more",
+            ThingKind::Listing => "Listing",
+        }
+    }
+}
+
+impl FromStr for ThingKind {
+    type Err = ParseFullnameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "t1" => Ok(ThingKind::Comment),
+            "t2" => Ok(ThingKind::Account),
+            "t3" => Ok(ThingKind::Link),
+            "t4" => Ok(ThingKind::Message),
+            "t5" => Ok(ThingKind::Subreddit),
+            "t6" => Ok(ThingKind::Award),
+            "more" => Ok(ThingKind::More),
+            "Listing" => Ok(ThingKind::Listing),
+            _ => Err(ParseFullnameError::Kind(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for ThingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.prefix())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseFullnameError {
+    /// Unknown thing kind
+    Kind(String),
+    /// The ID isn't a base-36 number
+    Id(String),
+    /// No kind and ID separated by an underscore
+    Format(String),
+}
+
+impl fmt::Display for ParseFullnameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseFullnameError::Kind(kind) => write!(f, "unknown thing kind {kind}"),
+            ParseFullnameError::Id(id) => write!(f, "invalid base-36 id {id}"),
+            ParseFullnameError::Format(s) => write!(f, "invalid fullname {s}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseFullnameError {}
+
+/// Encode an ID in base 36, with lowercase letters
+pub fn to_base36(mut id: u64) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut digits = vec![];
+    loop {
+        digits.push(DIGITS[(id % 36) as usize]);
+        id /= 36;
+        if id == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("base-36 digits are ASCII")
+}
+
+/// Decode a base-36 ID, case insensitive
+pub fn from_base36(id: &str) -> Result<u64, ParseFullnameError> {
+    // from_str_radix also accepts a leading sign
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return Err(ParseFullnameError::Id(id.to_owned()));
+    }
+    u64::from_str_radix(id, 36).map_err(|_| ParseFullnameError::Id(id.to_owned()))
+}
+
+/// The full 'Thing ID', consisting of a kind and a base-36 ID, serialized as a string like `t3_15bfi0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fullname {
+    pub kind: ThingKind,
+    pub id: u64,
+}
+
+impl Fullname {
+    pub fn new(kind: ThingKind, id: u64) -> Self {
+        Self { kind, id }
+    }
+
+    /// The base-36 ID without the kind
+    pub fn id36(&self) -> String {
+        to_base36(self.id)
+    }
+
+    /// Parse a fullname, or a bare base-36 ID of a thing of kind `kind`
+    pub fn parse_as(kind: ThingKind, s: &str) -> Result<Self, ParseFullnameError> {
+        if s.contains('_') {
+            s.parse()
+        } else {
+            Ok(Self::new(kind, from_base36(s)?))
+        }
+    }
+}
+
+impl FromStr for Fullname {
+    type Err = ParseFullnameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, id) = s
+            .split_once('_')
+            .ok_or_else(|| ParseFullnameError::Format(s.to_owned()))?;
+        Ok(Self::new(kind.parse()?, from_base36(id)?))
+    }
+}
+
+impl fmt::Display for Fullname {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.kind, self.id36())
+    }
+}
+
+impl Serialize for Fullname {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fullname {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// A base-36 ID without the kind, like in `/comments/{id}` and `?comment={id}`.
+/// A fullname is accepted as well, its kind is ignored.
+/// IDs made of digits only are base 36 as well, they are never taken as decimal IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Id36(pub u64);
+
+impl FromStr for Id36 {
+    type Err = ParseFullnameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = s.split_once('_').map_or(s, |(_, id)| id);
+        Ok(Self(from_base36(id)?))
+    }
+}
+
+impl fmt::Display for Id36 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&to_base36(self.0))
+    }
+}
+
+impl Serialize for Id36 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Id36 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_link() {
+        let fullname: Fullname = "t3_15bfi0".parse().unwrap();
+        assert_eq!(fullname, Fullname::new(ThingKind::Link, 69_397_560));
+        assert_eq!(fullname.id36(), "15bfi0");
+        assert_eq!(fullname.to_string(), "t3_15bfi0");
+    }
+
+    #[test]
+    fn round_trip() {
+        for s in [
+            "t1_0",
+            "t2_1w72",
+            "t3_15bfi0",
+            "t5_2qh1i",
+            "more_z",
+            "t6_zzzzzzz",
+        ] {
+            let fullname: Fullname = s.parse().unwrap();
+            assert_eq!(fullname.to_string(), s);
+
+            let json = serde_json::to_string(&fullname).unwrap();
+            assert_eq!(json, format!("\"{s}\""));
+            assert_eq!(serde_json::from_str::<Fullname>(&json).unwrap(), fullname);
+        }
+        let max = Fullname::new(ThingKind::Comment, u64::MAX);
+        assert_eq!(max.to_string().parse::<Fullname>().unwrap(), max);
+    }
+
+    #[test]
+    fn uppercase_id() {
+        let fullname: Fullname = "t3_15BFI0".parse().unwrap();
+        assert_eq!(fullname.to_string(), "t3_15bfi0");
+    }
+
+    #[test]
+    fn parse_as_bare_id() {
+        let fullname = Fullname::parse_as(ThingKind::Comment, "jxk2lw").unwrap();
+        assert_eq!(fullname.kind, ThingKind::Comment);
+        assert_eq!(fullname.to_string(), "t1_jxk2lw");
+
+        // A fullname keeps its own kind
+        let fullname = Fullname::parse_as(ThingKind::Comment, "t3_15bfi0").unwrap();
+        assert_eq!(fullname.kind, ThingKind::Link);
+
+        assert_eq!(
+            Fullname::parse_as(ThingKind::Comment, ""),
+            Err(ParseFullnameError::Id(String::new()))
+        );
+    }
+
+    #[test]
+    fn id36() {
+        let id: Id36 = "15bfi0".parse().unwrap();
+        assert_eq!(id, Id36(69_397_560));
+        assert_eq!("t3_15bfi0".parse::<Id36>().unwrap(), id);
+        assert_eq!(id.to_string(), "15bfi0");
+
+        // digits only are still base 36, never a decimal ID
+        assert_eq!("123".parse::<Id36>().unwrap(), Id36(1371));
+    }
+
+    #[test]
+    fn reject_invalid_ids() {
+        for id in ["", "+1", "-1", "1_", "a b", "é"] {
+            assert_eq!(from_base36(id), Err(ParseFullnameError::Id(id.to_owned())));
+        }
+        // u64::MAX is 3w5e11264sgsf
+        assert!(from_base36("3w5e11264sgsf").is_ok());
+        assert!(from_base36("3w5e11264sgsg").is_err());
+        assert!(from_base36("1000000000000000").is_err());
+
+        assert_eq!(
+            "t3_".parse::<Fullname>(),
+            Err(ParseFullnameError::Id(String::new()))
+        );
+        assert_eq!(
+            "t3_-1".parse::<Fullname>(),
+            Err(ParseFullnameError::Id("-1".to_owned()))
+        );
+        assert_eq!(
+            "t9_1".parse::<Fullname>(),
+            Err(ParseFullnameError::Kind("t9".to_owned()))
+        );
+        assert_eq!(
+            "15bfi0".parse::<Fullname>(),
+            Err(ParseFullnameError::Format("15bfi0".to_owned()))
+        );
+        assert!(serde_json::from_str::<Fullname>("\"t3_+1\"").is_err());
+    }
+}
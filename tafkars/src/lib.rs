@@ -1,22 +1,39 @@
 //! # The API formerly known as...
 //! Forked from [`roux`](https://docs.rs/roux/) to get just the API definitions and simplify a bit
+//!
+//! ## Features
+//! - `extra`: submissions, comments, subreddits, users and listings get an `extra` field,
+//!   a map of the fields that aren't modeled, so that they survive a round trip.
+//! - `strict`: deserializing these types fails on fields that aren't modeled.
+//!   It is a no-op together with `extra`, which keeps those fields instead of rejecting them.
+pub mod api;
 pub mod comment;
+pub mod empty_string;
+pub mod fullname;
 pub mod listing;
+pub mod modlog;
+pub mod prefs;
 pub mod submission;
 pub mod subreddit;
+pub mod trophy;
 pub mod user;
+pub mod wiki;
 
 use crate::comment::CommentData;
 use crate::listing::ListingData;
+use crate::modlog::ModActionData;
 use crate::submission::SubmissionData;
 use crate::subreddit::SubredditData;
+use crate::trophy::TrophyData;
 use crate::user::UserData;
+use crate::wiki::WikiPageData;
 use serde::{Deserialize, Serialize};
 
 /// Basic structure of a Reddit response.
 /// See: <https://github.com/reddit-archive/reddit/wiki/JSON>
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "kind", content = "data")]
+#[allow(clippy::large_enum_variant)]
 pub enum RedditThing {
     #[serde(rename = "t1")]
     Comment(CommentData),
@@ -26,6 +43,12 @@ pub enum RedditThing {
     Submission(SubmissionData),
     #[serde(rename = "t5")]
     Subreddit(SubredditData),
+    #[serde(rename = "t6")]
+    Trophy(TrophyData),
+    #[serde(rename = "wikipage")]
+    WikiPage(WikiPageData),
+    #[serde(rename = "modaction")]
+    ModAction(ModActionData),
     #[serde(rename = "Listing")]
     Listing(ListingData<RedditThing>),
 }
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 /// JSON list response.
 #[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra")),
+    serde(deny_unknown_fields)
+)]
 pub struct ListingData<T> {
     /// Modhash
     pub modhash: Option<String>,
@@ -12,6 +16,9 @@ pub struct ListingData<T> {
     pub before: Option<String>,
     /// A list of `things` that this Listing wraps.
     pub children: Vec<T>,
+    #[cfg(feature = "extra")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -30,6 +37,8 @@ impl<T> Listing<T> {
                 after: None,
                 before: None,
                 children: items,
+                #[cfg(feature = "extra")]
+                extra: Default::default(),
             },
         }
     }
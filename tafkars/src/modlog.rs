@@ -0,0 +1,60 @@
+//! # Subreddit Moderation Log Responses
+use crate::fullname::Fullname;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(tag = "kind", rename = "modaction")]
+pub struct ModAction {
+    pub data: ModActionData,
+}
+
+/// ModActionData
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ModActionData {
+    /// The ID of the action, e.g. `ModAction_8c5b2d4a-...`
+    pub id: String,
+    /// The kind of action, e.g. `removelink`, `approvecomment`, `banuser`, `sticky` or `lock`.
+    pub action: String,
+    /// The name of the moderator who took the action.
+    #[serde(rename = "mod")]
+    pub mod_: String,
+    /// The base-36 ID of the moderator.
+    pub mod_id36: Option<String>,
+    /// A timestamp of the time when the action was taken, in **UTC**.
+    pub created_utc: f64,
+    /// The subreddit the action was taken in (not including `/r/`).
+    pub subreddit: String,
+    /// The subreddit the action was taken in, including `r/`.
+    pub subreddit_name_prefixed: String,
+    /// The base-36 ID of the subreddit.
+    pub sr_id36: String,
+    /// Short details of the action, e.g. the duration of a ban.
+    pub details: Option<String>,
+    /// The reason given for the action.
+    pub description: Option<String>,
+    /// The fullname of the thing the action was taken on.
+    pub target_fullname: Option<Fullname>,
+    /// The name of the author of the target, or of the target user.
+    pub target_author: Option<String>,
+    /// The title of the target submission.
+    pub target_title: Option<String>,
+    /// The text of the target.
+    pub target_body: Option<String>,
+    /// The permalink of the target submission or comment.
+    pub target_permalink: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Query {
+    /// Only show actions of this kind.
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    /// Only show actions by these moderators, separated by commas.
+    #[serde(rename = "mod")]
+    pub mod_: Option<String>,
+    /// Show the actions after the action with this ID.
+    pub after: Option<String>,
+    /// Show the actions before the action with this ID.
+    pub before: Option<String>,
+    pub limit: Option<u32>,
+}
@@ -0,0 +1,53 @@
+//! # User Preferences
+use serde::{Deserialize, Serialize};
+
+/// Preferences of the API user, as returned by `/api/v1/me/prefs`.
+/// Only some of reddit's many preferences are included, `PATCH` requests may contain any subset.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Prefs {
+    /// Show NSFW content
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub over_18: Option<bool>,
+    /// Include NSFW results in searches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_include_over_18: Option<bool>,
+    /// Blur thumbnails of NSFW content
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_nsfw: Option<bool>,
+    /// Default sort order of comments, e.g. `confidence`, `top` or `new`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_comment_sort: Option<String>,
+    /// Show link flair
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_link_flair: Option<bool>,
+    /// Show user flair
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_flair: Option<bool>,
+    /// Hide upvote counts and scores
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hide_ups: Option<bool>,
+    /// Hide downvote counts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hide_downs: Option<bool>,
+    /// Send messages as emails
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_messages: Option<bool>,
+    /// Interface language, e.g. `en`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// Use the dark theme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nightmode: Option<bool>,
+    /// Hide submissions with a lower score
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_link_score: Option<i64>,
+    /// Collapse comments with a lower score
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_comment_score: Option<i64>,
+    /// Number of submissions per page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numsites: Option<u32>,
+    /// Number of comments to show by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_comments: Option<u32>,
+}
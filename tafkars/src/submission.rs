@@ -1,9 +1,15 @@
 //! # Subreddit Submission Responses
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use crate::fullname::{Fullname, Id36};
+use crate::subreddit::RichtextFlair;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
 
 /// SubmissionsData
 #[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra")),
+    serde(deny_unknown_fields)
+)]
 pub struct SubmissionData {
     /// The domain of the link (if link post) or self.subreddit (if self post).
     /// Domains do not include a protocol, e.g. `i.redd.it` or `self.learnprogramming`
@@ -11,9 +17,20 @@ pub struct SubmissionData {
     /// Contains the name of the moderator who banned this, if the logged-in user is a moderator
     /// of this subreddit and this is banned.
     pub banned_by: Option<String>,
-    // pub media_embed: MediaEmbed,
+    /// When this was removed, if the logged-in user is a moderator of this subreddit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub banned_at_utc: Option<f64>,
+    /// Embeddable HTML for the linked media. Empty if this is not a link to embeddable media.
+    #[serde(default)]
+    pub media_embed: MediaEmbed,
     /// The subreddit that this submission was posted in (not including `/r/`)
     pub subreddit: String,
+    /// The subreddit including the leading `r/`
+    pub subreddit_name_prefixed: Option<String>,
+    /// The number of subscribers of the subreddit, if known.
+    pub subreddit_subscribers: Option<u64>,
+    /// The type of the subreddit, e.g. `public`, `restricted` or `private`.
+    pub subreddit_type: Option<String>,
     /// If this is a self post, it contains the HTML of the post body. Otherwise, it is `None`.
     pub selftext_html: Option<String>,
     /// The self text in **Markdown** format, if this is a self post. Unlike `selftext_html`, this
@@ -24,13 +41,21 @@ pub struct SubmissionData {
     pub likes: Option<bool>,
     /// Suggested sort order
     pub suggested_sort: Option<SortOrder>,
-    // skipped user_reports and secure_media
+    /// Reports by users as `[reason, count]`, if the logged-in user is a moderator of this subreddit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_reports: Option<Vec<UserReport>>,
+    /// Reports by moderators as `[reason, moderator]`, if the logged-in user is a moderator of this subreddit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mod_reports: Option<Vec<ModReport>>,
+    /// Same as `media`, but only using HTTPS URLs.
+    pub secure_media: Option<Media>,
     /// If this post is flaired, this set to `Some(FLAIR TEXT)`. Otherwise, it is `None`.
     /// Link flairs **can** be empty strings.
     pub link_flair_text: Option<String>,
     /// The ID of the post in base-36 form, as used in Reddit's links.
     pub id: String,
-    // skipped from_kind
+    /// Only used by messages, always `None`.
+    pub from_kind: Option<String>,
     /// The amount of times that a user has been gilded (gifted Reddit Gold).
     pub gilded: u64,
     /// This is `true` if Reddit has archived the submission (usually done after 6 months).
@@ -38,10 +63,17 @@ pub struct SubmissionData {
     pub archived: bool,
     /// This is `true` if the logged-in user has already followed this link, otherwise `false`.
     pub clicked: bool,
-    // skipped report_reasons
+    /// The reasons this was reported for, if the logged-in user is a moderator of this subreddit.
+    pub report_reasons: Option<Vec<String>>,
+    /// This is `true` if a moderator chose to ignore further reports of this submission.
+    #[serde(default)]
+    pub ignore_reports: bool,
     /// The name of the author of the submission (not including the leading `/u/`)
     pub author: String,
-    // skipped media
+    /// The fullname of the author, e.g. `t2_1w72`. Missing for deleted authors.
+    pub author_fullname: Option<Fullname>,
+    /// Video hosted by the site or metadata of media embedded from other sites, if this links to media.
+    pub media: Option<Media>,
     /// The overall points score of this post, as shown on the upvote counter. This is the
     /// same as upvotes - downvotes (however, this figure may be fuzzed by Reddit, and may not
     /// be exact)
@@ -49,13 +81,24 @@ pub struct SubmissionData {
     /// This contains the name of the user who approved this submission. This is `None` unless
     /// you are a mod of the subreddit **and** a user has approved this post.
     pub approved_by: Option<String>,
+    /// When this was approved, if the logged-in user is a moderator of this subreddit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved_at_utc: Option<f64>,
+    /// This is `Some(true)` if this has been removed and the logged-in user is a moderator of this subreddit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed: Option<bool>,
+    /// This is `Some(true)` if this has been approved and the logged-in user is a moderator of this subreddit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved: Option<bool>,
     /// This is `true` if the 'nsfw' option has been selected for this submission.
     pub over_18: bool,
     /// This is `true` if the 'spoiler' option has been selected for this submission.
     pub spoiler: bool,
     /// This is `true` if the logged-in user has clicked 'hide' on this post.
     pub hidden: bool,
-    // TODO: skipped preview
+    /// Preview images for this submission in various resolutions, if available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<Preview>,
     /// The number of comment replies to this submission.
     pub num_comments: u64,
     /// The URL to the link thumbnail. This is "self" if this is a self post, or "default" if
@@ -66,32 +109,60 @@ pub struct SubmissionData {
     /// This is `true` if the score is being hidden.
     pub hide_score: bool,
     /// This is `false` if the submission is not edited and is the edit timestamp if it is edited.
-    /// Access through the functions of `Submission` instead.
-    pub edited: Value,
+    #[serde(default)]
+    pub edited: Edited,
     /// The CSS class set for the link's flair (if available), otherwise `None`.
     pub link_flair_css_class: Option<String>,
+    /// The type of the link's flair, `text` or `richtext`.
+    pub link_flair_type: Option<String>,
+    /// The elements of the link's flair, if its type is `richtext`.
+    #[serde(default)]
+    pub link_flair_richtext: Vec<RichtextFlair>,
+    /// The ID of the flair template the link's flair is based on.
+    pub link_flair_template_id: Option<String>,
+    /// The background color of the link's flair, e.g. `#ff4500`. Can be an empty string.
+    pub link_flair_background_color: Option<String>,
+    /// The text color of the link's flair, `dark` or `light`.
+    pub link_flair_text_color: Option<String>,
     /// The CSS class set for the author's flair (if available). If there is no flair, this is
     /// `None`.
     pub author_flair_css_class: Option<String>,
+    /// The type of the author's flair, `text` or `richtext`.
+    pub author_flair_type: Option<String>,
+    /// The elements of the author's flair, if its type is `richtext`.
+    #[serde(default)]
+    pub author_flair_richtext: Vec<RichtextFlair>,
+    /// The ID of the flair template the author's flair is based on.
+    pub author_flair_template_id: Option<String>,
+    /// The background color of the author's flair. Can be an empty string.
+    pub author_flair_background_color: Option<String>,
+    /// The text color of the author's flair, `dark` or `light`.
+    pub author_flair_text_color: Option<String>,
     /// The number of downvotes (fuzzed; see `score` for further explanation)
     pub downs: i64,
     /// The number of upvotes (fuzzed; see `score` for further explanation)
     pub ups: i64,
     /// The ratio of upvotes to total votes. Equal to upvotes/(upvotes+downvotes) (fuzzed; see `score` for further explanation)
     pub upvote_ratio: f64,
-    // TODO: skipped secure_media_embed
+    /// Same as `media_embed`, but only using HTTPS URLs.
+    #[serde(default)]
+    pub secure_media_embed: MediaEmbed,
     /// True if the logged-in user has saved this submission.
     pub saved: bool,
     /// The reason for the post removal, if you are a moderator **and** this post has been
     /// removed.
     pub removal_reason: Option<String>,
-    // TODO: skipped post_hint
+    /// The type of content this submission links to, e.g. `self`, `link`, `image`, `hosted:video` or `rich:video`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_hint: Option<String>,
     /// This is `true` if this submission is stickied (an 'annoucement' thread)
     pub stickied: bool,
-    // TODO: skipped from
+    /// Only used by messages, always `None`.
+    pub from: Option<String>,
     /// This is `true` if this is a self post.
     pub is_self: bool,
-    // TODO: skipped from_id
+    /// Only used by messages, always `None`.
+    pub from_id: Option<String>,
     /// The permanent, long link for this submission.
     pub permalink: String,
     /// This is `true` if the submission has been locked by a moderator, and no replies can be
@@ -105,7 +176,7 @@ pub struct SubmissionData {
     /// - t5_ - Subreddit
     /// - t6_ - Award
     /// - t8_ - PromoCampaign
-    pub name: String,
+    pub name: Option<Fullname>,
     /// A timestamp of the time when the post was created, in the logged-in user's **local**
     /// time.
     pub created: f64,
@@ -126,8 +197,39 @@ pub struct SubmissionData {
     pub visited: bool,
     /// The number of reports, if the user is a moderator of this subreddit.
     pub num_reports: Option<u64>,
+    /// This is `true` if this links to a video hosted by the site, see `media.reddit_video`.
     pub is_video: bool,
+    /// The fullname of the submission this is a crosspost of, if this is a crosspost.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crosspost_parent: Option<Fullname>,
+    /// Contains the submission this is a crosspost of, if this is a crosspost.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crosspost_parent_list: Option<Vec<SubmissionData>>,
+    /// The number of times this submission has been crossposted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_crossposts: Option<u64>,
     pub can_mod_post: bool,
+    /// The awards given to this submission.
+    #[serde(default)]
+    pub all_awardings: Vec<Awarding>,
+    /// The number of awards given to this submission.
+    #[serde(default)]
+    pub total_awards_received: u64,
+    /// This is `true` if this is a gallery of images, see `gallery_data` and `media_metadata`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_gallery: Option<bool>,
+    /// The images of a gallery, in order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gallery_data: Option<GalleryData>,
+    /// Images and videos of galleries and of self posts embedding media, by media ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_metadata: Option<HashMap<String, MediaMetadata>>,
+    /// The poll, if this is a poll post.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_data: Option<PollData>,
+    #[cfg(feature = "extra")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -136,6 +238,354 @@ pub struct Submission {
     pub data: SubmissionData,
 }
 
+/// When a submission or comment was last edited, serialized as `false` or the edit timestamp
+/// Reddit sends whole seconds, like `1690000000`, which are read as `f64` and written back as `1690000000.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Edited {
+    #[default]
+    No,
+    /// Edit timestamp (UTC)
+    At(f64),
+}
+
+impl Edited {
+    pub fn timestamp(self) -> Option<f64> {
+        match self {
+            Edited::No => None,
+            Edited::At(timestamp) => Some(timestamp),
+        }
+    }
+}
+
+impl From<Option<f64>> for Edited {
+    fn from(timestamp: Option<f64>) -> Self {
+        timestamp.map_or(Edited::No, Edited::At)
+    }
+}
+
+impl Serialize for Edited {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Edited::No => serializer.serialize_bool(false),
+            Edited::At(timestamp) => serializer.serialize_f64(*timestamp),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Edited {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Bool(bool),
+            Timestamp(f64),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Bool(false) => Ok(Edited::No),
+            Repr::Bool(true) => Err(de::Error::invalid_value(
+                de::Unexpected::Bool(true),
+                &"false or a timestamp",
+            )),
+            Repr::Timestamp(timestamp) => Ok(Edited::At(timestamp)),
+        }
+    }
+}
+
+/// A user report of a submission or comment, all reports with the same reason are counted together
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UserReport(
+    /// Reason
+    pub String,
+    /// Number of reports with this reason
+    pub u32,
+    /// Snoozed
+    #[serde(default)]
+    pub bool,
+    /// Can snooze
+    #[serde(default)]
+    pub bool,
+);
+
+/// A moderator report of a submission or comment
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ModReport(
+    /// Reason
+    pub String,
+    /// Name of the moderator
+    pub String,
+);
+
+/// An award given to a submission or comment, with the number of times it was given.
+/// Old awards can lack any of the fields.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct Awarding {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// How many times the award was given
+    pub count: u64,
+    /// The price of the award in coins
+    pub coin_price: u64,
+    /// The type of the award, e.g. `global` or `community`
+    pub award_type: Option<String>,
+    pub icon_url: String,
+    pub icon_width: Option<u32>,
+    pub icon_height: Option<u32>,
+    /// Non-animated version of the icon
+    pub static_icon_url: Option<String>,
+    /// The icon in various resolutions
+    pub resized_icons: Vec<ImageSource>,
+}
+
+/// The images of a gallery submission
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct GalleryData {
+    pub items: Vec<GalleryItem>,
+}
+
+/// An image of a gallery, the image itself is in `media_metadata` under `media_id`
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct GalleryItem {
+    pub media_id: String,
+    pub id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    /// A link shown with the image
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outbound_url: Option<String>,
+}
+
+/// An image or video of a gallery or embedded in a self post
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct MediaMetadata {
+    /// `valid` once the media has been processed, otherwise e.g. `unprocessed` or `failed`.
+    pub status: String,
+    /// The kind of media, e.g. `Image`, `AnimatedImage` or `RedditVideo`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    /// The MIME type, e.g. `image/jpg`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub m: Option<String>,
+    /// The media ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The original media
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s: Option<MediaSource>,
+    /// Scaled down previews
+    #[serde(default)]
+    pub p: Vec<MediaSource>,
+    /// URL of the DASH playlist of a video
+    #[serde(rename = "dashUrl", skip_serializing_if = "Option::is_none")]
+    pub dash_url: Option<String>,
+    /// URL of the HLS playlist of a video
+    #[serde(rename = "hlsUrl", skip_serializing_if = "Option::is_none")]
+    pub hls_url: Option<String>,
+    /// This is `true` if the video is a GIF converted to a video
+    #[serde(rename = "isGif", skip_serializing_if = "Option::is_none")]
+    pub is_gif: Option<bool>,
+}
+
+/// A version of a media item. Images have `u`, animated images have `gif` and `mp4`.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct MediaSource {
+    /// Width in pixels
+    pub x: u32,
+    /// Height in pixels
+    pub y: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub u: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gif: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mp4: Option<String>,
+}
+
+/// The poll of a poll submission
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct PollData {
+    pub options: Vec<PollOption>,
+    /// The number of votes on all options
+    pub total_vote_count: u64,
+    /// When voting ends, in milliseconds since the epoch (UTC)
+    pub voting_end_timestamp: f64,
+    /// The ID of the option the logged-in user voted for
+    pub user_selection: Option<String>,
+    /// This is `true` if this is a prediction
+    #[serde(default)]
+    pub is_prediction: bool,
+    /// The ID of the winning option of a resolved prediction
+    pub resolved_option_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct PollOption {
+    pub id: String,
+    pub text: String,
+    /// The number of votes, only known once voting ended or the logged-in user voted
+    pub vote_count: Option<u64>,
+}
+
+/// Media of a submission, used for `media` and `secure_media`
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Media {
+    /// A video hosted by the site itself.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reddit_video: Option<RedditVideo>,
+    /// oEmbed metadata of media embedded from another site.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oembed: Option<OEmbed>,
+    /// The domain of the embedded media's provider, e.g. `youtube.com`.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+}
+
+/// A video hosted by the site itself
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct RedditVideo {
+    /// Direct URL of the video file, playable without HLS or DASH support.
+    pub fallback_url: String,
+    /// The width of the video in pixels, if known.
+    pub width: Option<u32>,
+    /// The height of the video in pixels, if known.
+    pub height: Option<u32>,
+    /// The length of the video in seconds, if known.
+    pub duration: Option<u32>,
+    /// This is `true` if this is a GIF converted to a video, which has no sound and should loop.
+    pub is_gif: bool,
+    /// URL of the HLS playlist, if available.
+    pub hls_url: Option<String>,
+    /// URL of the DASH playlist, if available.
+    pub dash_url: Option<String>,
+    /// URL of a low quality version of the video for scrubbing, if available.
+    pub scrubber_media_url: Option<String>,
+    /// Bitrate of the fallback video, if known.
+    pub bitrate_kbps: Option<u32>,
+    /// This is `completed` once the video is ready to be played.
+    pub transcoding_status: Option<String>,
+}
+
+/// oEmbed metadata of embedded media, see <https://oembed.com/>
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct OEmbed {
+    /// One of `video`, `rich`, `photo` or `link`.
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// The oEmbed version, always `1.0`.
+    pub version: Option<String>,
+    /// The title of the embedded media.
+    pub title: Option<String>,
+    /// A description of the embedded media.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The name of the provider, e.g. `YouTube`.
+    pub provider_name: Option<String>,
+    /// The URL of the provider, e.g. `https://www.youtube.com/`.
+    pub provider_url: Option<String>,
+    /// The name of the author of the media.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_name: Option<String>,
+    /// The URL of the author of the media.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_url: Option<String>,
+    /// HTML to embed the media, HTML-escaped unless `raw_json=1` was requested.
+    pub html: Option<String>,
+    /// The width of the embedded media in pixels.
+    pub width: Option<u32>,
+    /// The height of the embedded media in pixels.
+    pub height: Option<u32>,
+    /// URL of a thumbnail of the embedded media.
+    pub thumbnail_url: Option<String>,
+    /// The width of the thumbnail in pixels.
+    pub thumbnail_width: Option<u32>,
+    /// The height of the thumbnail in pixels.
+    pub thumbnail_height: Option<u32>,
+}
+
+/// Embeddable HTML of a submission's media. All fields are missing if there is nothing to embed.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct MediaEmbed {
+    /// HTML to embed the media, HTML-escaped unless `raw_json=1` was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// The width of the embedded media in pixels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// The height of the embedded media in pixels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Whether or not the embedding frame should be scrollable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scrolling: Option<bool>,
+    /// URL of a page that embeds the media, if the site hosts one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_domain_url: Option<String>,
+}
+
+/// Preview images of a submission
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Preview {
+    /// Usually contains a single image.
+    pub images: Vec<PreviewImage>,
+    /// This is `true` if the preview is the submission's main content, e.g. for image posts.
+    pub enabled: bool,
+}
+
+/// A preview image in its original size and scaled down resolutions
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PreviewImage {
+    /// The image in its original size.
+    pub source: ImageSource,
+    /// Scaled down versions of the image, ordered by increasing width.
+    pub resolutions: Vec<ImageSource>,
+    /// Alternative versions of the image, e.g. blurred for NSFW submissions.
+    pub variants: PreviewVariants,
+    /// An opaque identifier for the image.
+    pub id: String,
+}
+
+/// A single version of an image
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct ImageSource {
+    /// The URL of the image. Note that `&` is HTML-escaped as `&amp;` unless `raw_json=1` was requested.
+    pub url: String,
+    /// The width of the image in pixels. Can be missing if the API can't know the exact size,
+    /// in that case the image is at most this wide.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// The height of the image in pixels, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+}
+
+/// Alternative versions of a preview image
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PreviewVariants {
+    /// Animated version, if the image is a GIF.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gif: Option<PreviewVariant>,
+    /// Video version, if the image is a GIF.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mp4: Option<PreviewVariant>,
+    /// Blurred version, for NSFW and spoiler submissions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub obfuscated: Option<PreviewVariant>,
+    /// Blurred version, for NSFW submissions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nsfw: Option<PreviewVariant>,
+}
+
+/// An alternative version of a preview image
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PreviewVariant {
+    /// The variant in its original size.
+    pub source: ImageSource,
+    /// Scaled down versions of the variant, ordered by increasing width.
+    pub resolutions: Vec<ImageSource>,
+}
+
 /// Ways to sort the comments on a submission
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -156,7 +606,7 @@ pub enum SortOrder {
 /// Query parameters for the comments endpoint, get the comments for a submission
 pub struct Query {
     /// if set, get replies subtree for this comment
-    pub comment: Option<u64>, // TODO: parse base36
+    pub comment: Option<Id36>,
     /// number of parent comments to include in response
     pub context: Option<u8>,
     // #[serde(flatten)] // Not used due to: https://github.com/samscott89/serde_qs/issues/14
@@ -182,7 +632,56 @@ pub struct Query {
 #[derive(Debug, Deserialize)]
 pub struct CommentContext {
     /// comment id
-    comment: u64, // TODO: parse base36
+    pub comment: Id36,
     /// number of parent comments to include in response
-    context: Option<u8>,
+    pub context: Option<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edited_round_trip() {
+        for (json, edited) in [
+            ("false", Edited::No),
+            ("1690000000.5", Edited::At(1_690_000_000.5)),
+        ] {
+            assert_eq!(serde_json::from_str::<Edited>(json).unwrap(), edited);
+            assert_eq!(serde_json::to_string(&edited).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn edited_integer_becomes_float() {
+        let edited: Edited = serde_json::from_str("1690000000").unwrap();
+        assert_eq!(edited, Edited::At(1_690_000_000.0));
+        assert_eq!(serde_json::to_string(&edited).unwrap(), "1690000000.0");
+    }
+
+    #[test]
+    fn edited_rejects_true() {
+        assert!(serde_json::from_str::<Edited>("true").is_err());
+        assert!(serde_json::from_str::<Edited>("\"yes\"").is_err());
+    }
+
+    #[test]
+    fn edited_missing() {
+        let data: crate::comment::CommentData = serde_json::from_str("{}").unwrap();
+        assert_eq!(data.edited, Edited::No);
+
+        let mut json = serde_json::to_value(SubmissionData::default()).unwrap();
+        json.as_object_mut().unwrap().remove("edited");
+        let data: SubmissionData = serde_json::from_value(json).unwrap();
+        assert_eq!(data.edited, Edited::No);
+    }
+
+    #[test]
+    fn old_awarding() {
+        let awarding: Awarding = serde_json::from_str(r#"{"name": "Silver", "count": 2}"#).unwrap();
+        assert_eq!(awarding.name, "Silver");
+        assert_eq!(awarding.count, 2);
+        assert!(awarding.icon_url.is_empty());
+        assert!(awarding.resized_icons.is_empty());
+    }
 }
@@ -1,4 +1,5 @@
 //! # Subreddit Responses
+use crate::fullname::Fullname;
 use crate::submission;
 use serde::{Deserialize, Serialize};
 
@@ -19,19 +20,28 @@ pub enum AccountsActive {
     Vector(Vec<u8>),
 }
 
-/// If the API user has user flair in this subreddit, and its user_flair_type is richtext,
-/// this will be an array containing two string elements which define the user's flair.
-#[allow(dead_code)]
-#[derive(Debug, Serialize, Deserialize)]
+/// An element of a flair whose type is `richtext`, either text or an emoji
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct RichtextFlair {
-    /// contains the string text
-    e: String,
-    /// contains the literal string that comprises the user's flair.
-    t: String,
+    /// The type of the element, `text` or `emoji`
+    pub e: String,
+    /// The text of a text element
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub t: Option<String>,
+    /// The short code of an emoji, e.g. `:star:`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub a: Option<String>,
+    /// The image URL of an emoji
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub u: Option<String>,
 }
 
 /// SubredditData
 #[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra")),
+    serde(deny_unknown_fields)
+)]
 pub struct SubredditData {
     /// The HTML hex code of the API user's flair background color in this subreddit,
     /// if any. If the API user has no flair, or no background color is defined, this will be null.
@@ -116,7 +126,7 @@ pub struct SubredditData {
     pub videostream_links_count: Option<u8>,
     /// The fullname identifier of this subreddit. This is a combination of the thing kind (t5) and the id,
     /// separated by an underscore, e.g. t5_2qh38
-    pub name: Option<String>,
+    pub name: Option<Fullname>,
     /// Whether or not this subreddit is quarantined.
     /// This is a restricted property. To access a quarantined subreddit through this endpoint,
     /// the API user must have affirmatively clicked "continue" on its quarantine interstitial page at some point,
@@ -319,10 +329,13 @@ pub struct SubredditData {
     pub mobile_banner_image: Option<String>,
     /// Whether or not the API user is a moderator of this subreddit.
     pub user_is_moderator: Option<bool>,
+    #[cfg(feature = "extra")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Ways to sort submissions in a subreddit
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SortOrder {
     Hot,
@@ -334,7 +347,7 @@ pub enum SortOrder {
 }
 
 /// Time filters for controversial and top sorting
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum FilterTime {
     Hour,
@@ -345,8 +358,51 @@ pub enum FilterTime {
     All,
 }
 
+/// Response of the `/r/{subreddit}/about/rules` endpoint.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Rules {
+    /// The rules configured by this subreddit's moderators, in order of priority.
+    pub rules: Vec<Rule>,
+    /// Site-wide rules that can be chosen as report reasons in every subreddit.
+    pub site_rules: Vec<String>,
+}
+
+/// A single subreddit rule.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Rule {
+    /// What this rule applies to, one of `link`, `comment` or `all`.
+    pub kind: String,
+    /// The full description of the rule in **Markdown** format. Can be an empty string.
+    pub description: String,
+    /// The contents of description converted to HTML. If no description has been configured, this will be null.
+    pub description_html: Option<String>,
+    /// The title of the rule, at most 100 characters.
+    pub short_name: String,
+    /// The text shown as the report reason when reporting something for breaking this rule.
+    pub violation_reason: String,
+    /// The unix epoch timestamp at which this rule was created.
+    pub created_utc: f64,
+    /// The position of this rule in the list of rules, starting at 0.
+    pub priority: u32,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Query {
     #[serde(rename = "t")]
     pub time: Option<FilterTime>,
 }
+
+/// Kind of things to show in moderation listings
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModListingOnly {
+    Links,
+    Comments,
+}
+
+/// Query of moderation listings like `about/reports` and `about/modqueue`
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ModListingQuery {
+    pub only: Option<ModListingOnly>,
+    pub limit: Option<u32>,
+}
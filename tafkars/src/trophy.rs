@@ -0,0 +1,41 @@
+//! # User Trophy Responses
+use serde::{Deserialize, Serialize};
+
+/// The trophies of a user, as returned by `/api/v1/user/{username}/trophies`
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(tag = "kind", rename = "TrophyList")]
+pub struct TrophyList {
+    pub data: TrophyListData,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct TrophyListData {
+    pub trophies: Vec<Trophy>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(tag = "kind", rename = "t6")]
+pub struct Trophy {
+    pub data: TrophyData,
+}
+
+/// TrophyData
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct TrophyData {
+    /// The name of the trophy, e.g. `Five-Year Club`.
+    pub name: String,
+    /// URL of a 70x70 icon of the trophy.
+    pub icon_70: Option<String>,
+    /// URL of a 40x40 icon of the trophy.
+    pub icon_40: Option<String>,
+    /// The ID of this user's trophy, if it is unique to the user.
+    pub id: Option<String>,
+    /// The ID of the award this trophy is for.
+    pub award_id: Option<String>,
+    /// A link related to the trophy.
+    pub url: Option<String>,
+    /// A description of why the trophy was granted.
+    pub description: Option<String>,
+    /// A timestamp of the time when the trophy was granted, in **UTC**.
+    pub granted_at: Option<f64>,
+}
@@ -1,3 +1,4 @@
+use crate::fullname::Fullname;
 use crate::subreddit;
 use crate::subreddit::FilterTime;
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,10 @@ pub struct User {
 
 /// AboutData
 #[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra")),
+    serde(deny_unknown_fields)
+)]
 pub struct UserData {
     /// Is employee
     pub is_employee: Option<bool>,
@@ -136,6 +141,9 @@ pub struct UserData {
     pub has_subscribed: Option<bool>,
     /// accept pms
     pub accept_pms: Option<bool>,
+    #[cfg(feature = "extra")]
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -144,3 +152,60 @@ pub struct SubmissionQuery {
     #[serde(rename = "t")]
     pub time: Option<FilterTime>,
 }
+
+/// A list of users in a relationship with the API user or a subreddit, like banned users
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(tag = "kind", rename = "UserList")]
+pub struct UserList {
+    pub data: UserListData,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct UserListData {
+    pub children: Vec<RelUser>,
+    pub after: Option<String>,
+    pub before: Option<String>,
+}
+
+/// A user in a `UserList`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelUser {
+    /// A timestamp of the time when the relationship was created, in **UTC**.
+    pub date: f64,
+    /// The ID of the relationship, e.g. `rb_1a2b3c` for bans.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rel_id: Option<String>,
+    /// The name of the user.
+    pub name: String,
+    /// The fullname of the user.
+    pub id: Fullname,
+    /// Days left until a temporary ban expires, `None` for permanent bans and other relationships.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_left: Option<u32>,
+    /// Note left by a moderator, e.g. the reason for a ban.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct UserListQuery {
+    /// Only list this user.
+    pub user: Option<String>,
+}
+
+/// The API user's karma in each subreddit, as returned by `/api/v1/me/karma`
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(tag = "kind", rename = "KarmaList")]
+pub struct KarmaList {
+    pub data: Vec<SubredditKarma>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct SubredditKarma {
+    /// The subreddit (not including `/r/`)
+    pub sr: String,
+    /// Karma from submissions
+    pub link_karma: i64,
+    /// Karma from comments
+    pub comment_karma: i64,
+}
@@ -0,0 +1,28 @@
+//! # Subreddit Wiki Responses
+use crate::user::User;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(tag = "kind", rename = "wikipage")]
+pub struct WikiPage {
+    pub data: WikiPageData,
+}
+
+/// WikiPageData
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct WikiPageData {
+    /// The content of the page in **Markdown** format.
+    pub content_md: String,
+    /// The content of the page converted to HTML.
+    pub content_html: String,
+    /// Whether or not the API user may edit this page.
+    pub may_revise: bool,
+    /// The reason given for the latest revision, if any.
+    pub reason: Option<String>,
+    /// The unix epoch timestamp of the latest revision.
+    pub revision_date: Option<f64>,
+    /// The user that made the latest revision. This will be null if the author is unknown.
+    pub revision_by: Option<User>,
+    /// The ID of the latest revision.
+    pub revision_id: Option<String>,
+}